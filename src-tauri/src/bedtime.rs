@@ -0,0 +1,101 @@
+use crate::chatbox::{notify_chatbox, ChatboxEvent};
+use crate::config::load_settings;
+use crate::osc::send_osc_to_vrchat;
+use crate::quiet::QuietDecision;
+use crate::types::AppStateMutex;
+use chrono::{DateTime, Local};
+use rosc::OscType;
+use serde::{Deserialize, Serialize};
+use tokio::time::{sleep, Duration};
+
+// BedtimeReminderパラメータをONにしておく時間
+const BEDTIME_REMINDER_PULSE: Duration = Duration::from_secs(10);
+
+/// 就寝リマインダーの設定
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BedtimeSettings {
+    pub enabled: bool,              // 就寝リマインダーを有効にするかどうか
+    pub sleep_goal_minutes: u32,    // 目標の睡眠時間
+    pub remind_before_minutes: u32, // 就寝時刻の何分前に知らせるか
+    pub use_chatbox: bool,          // チャットボックスで知らせるかどうか
+    pub use_parameter: bool,        // BedtimeReminderパラメータで知らせるかどうか
+}
+
+impl Default for BedtimeSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            sleep_goal_minutes: 480,
+            remind_before_minutes: 30,
+            use_chatbox: true,
+            use_parameter: true,
+        }
+    }
+}
+
+impl BedtimeSettings {
+    // 各値を有効範囲に丸め込み
+    pub fn clamped(self) -> Self {
+        Self {
+            sleep_goal_minutes: self.sleep_goal_minutes.clamp(60, 720),
+            remind_before_minutes: self.remind_before_minutes.min(180),
+            ..self
+        }
+    }
+
+    // 起床時刻から就寝時刻を計算
+    pub fn bedtime_for(&self, wake_at: DateTime<Local>) -> DateTime<Local> {
+        wake_at - chrono::Duration::minutes(self.sleep_goal_minutes as i64)
+    }
+
+    // 就寝時刻からリマインダーの時刻を計算
+    pub fn reminder_for(&self, bedtime: DateTime<Local>) -> DateTime<Local> {
+        bedtime - chrono::Duration::minutes(self.remind_before_minutes as i64)
+    }
+}
+
+// 就寝時刻が近いことをチャットボックスとパラメータで知らせる
+pub async fn remind_bedtime(
+    state: &AppStateMutex,
+    settings: &BedtimeSettings,
+    bedtime: DateTime<Local>,
+) {
+    // おやすみ時間中は知らせない（遅らせると就寝時刻を過ぎてしまうため破棄する）
+    let quiet_hours = load_settings().quiet_hours.clamped();
+    if quiet_hours.decide(false, Local::now()) != QuietDecision::Allow {
+        println!("Bedtime reminder dropped by quiet hours.");
+        return;
+    }
+    println!("Bedtime reminder: bedtime at {}", bedtime.format("%H:%M"));
+
+    if settings.use_chatbox {
+        let state_clone = state.clone();
+        let time = bedtime.format("%H:%M").to_string();
+        tokio::spawn(async move {
+            notify_chatbox(
+                &state_clone,
+                ChatboxEvent::BedtimeReminder,
+                &[("time", time)],
+            )
+            .await;
+        });
+    }
+
+    if settings.use_parameter {
+        for active in [true, false] {
+            if let Err(e) = send_osc_to_vrchat(
+                "/avatar/parameters/BedtimeReminder",
+                vec![OscType::Bool(active)],
+                state,
+            )
+            .await
+            {
+                eprintln!("Failed to send bedtime reminder: {}", e);
+            }
+            if active {
+                sleep(BEDTIME_REMINDER_PULSE).await;
+            }
+        }
+    }
+}
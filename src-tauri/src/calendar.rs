@@ -0,0 +1,1100 @@
+use crate::chatbox::{notify_chatbox, ChatboxEvent};
+use crate::config::load_settings;
+use crate::osc::send_osc_to_vrchat;
+use crate::quiet::QuietDecision;
+use crate::schedule::weekday_allowed;
+use crate::timer::handle_timer_event;
+use crate::types::{
+    AlarmSettings, AlarmSource, AlarmType, AppStateMutex, EventKind, TimerEvent, TimerManagerMutex,
+};
+use crate::utils::{vrc_float_to_hour, vrc_float_to_minute};
+use chrono::{
+    DateTime, Datelike, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc, Weekday,
+};
+use rosc::OscType;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use tokio::time::{sleep, Duration};
+
+// 繰り返しを展開する最大の周期数（日ごとの予定で約50年分）
+const CALENDAR_MAX_PERIODS: u32 = 20000;
+// CalendarReminderパラメータをONにしておく時間
+const CALENDAR_REMINDER_PULSE: Duration = Duration::from_secs(10);
+// iCalendarの曜日（0=日曜日、6=土曜日）
+pub const ICS_WEEKDAYS: [&str; 7] = ["SU", "MO", "TU", "WE", "TH", "FR", "SA"];
+// Windowsのタイムゾーン名とIANAのタイムゾーン名の対応（主なもの）
+const WINDOWS_ZONES: [(&str, &str); 22] = [
+    ("UTC", "Etc/UTC"),
+    ("Tokyo Standard Time", "Asia/Tokyo"),
+    ("Korea Standard Time", "Asia/Seoul"),
+    ("China Standard Time", "Asia/Shanghai"),
+    ("Taipei Standard Time", "Asia/Taipei"),
+    ("Singapore Standard Time", "Asia/Singapore"),
+    ("SE Asia Standard Time", "Asia/Bangkok"),
+    ("India Standard Time", "Asia/Kolkata"),
+    ("Arabian Standard Time", "Asia/Dubai"),
+    ("Russian Standard Time", "Europe/Moscow"),
+    ("GMT Standard Time", "Europe/London"),
+    ("W. Europe Standard Time", "Europe/Berlin"),
+    ("Romance Standard Time", "Europe/Paris"),
+    ("Eastern Standard Time", "America/New_York"),
+    ("Central Standard Time", "America/Chicago"),
+    ("Mountain Standard Time", "America/Denver"),
+    ("Pacific Standard Time", "America/Los_Angeles"),
+    ("Alaskan Standard Time", "America/Anchorage"),
+    ("Hawaiian Standard Time", "Pacific/Honolulu"),
+    ("E. South America Standard Time", "America/Sao_Paulo"),
+    ("AUS Eastern Standard Time", "Australia/Sydney"),
+    ("New Zealand Standard Time", "Pacific/Auckland"),
+];
+
+/// iCalendarファイルの読み込みの設定
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CalendarImportSettings {
+    pub path: String,           // 読み込む.icsファイル（空の場合は読み込まない）
+    pub reload_minutes: u32,    // ファイルを読み込み直す間隔
+    pub ring_at_start: bool,    // 予定の開始時刻にアラームを鳴らすかどうか
+    pub remind_chatbox: bool,   // 予定の通知をチャットボックスで知らせるかどうか
+    pub remind_parameter: bool, // 予定の通知をCalendarReminderパラメータで知らせるかどうか
+}
+
+impl Default for CalendarImportSettings {
+    fn default() -> Self {
+        Self {
+            path: String::new(),
+            reload_minutes: 15,
+            ring_at_start: true,
+            remind_chatbox: true,
+            remind_parameter: true,
+        }
+    }
+}
+
+impl CalendarImportSettings {
+    // 各値を有効範囲に丸め込み
+    pub fn clamped(self) -> Self {
+        Self {
+            reload_minutes: self.reload_minutes.clamp(1, 1440),
+            ..self
+        }
+    }
+}
+
+/// 繰り返しの頻度
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+impl Frequency {
+    fn as_ics(self) -> &'static str {
+        match self {
+            Frequency::Daily => "DAILY",
+            Frequency::Weekly => "WEEKLY",
+            Frequency::Monthly => "MONTHLY",
+            Frequency::Yearly => "YEARLY",
+        }
+    }
+}
+
+/// 予定の繰り返し（RRULEのうち対応している部分）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Recurrence {
+    pub frequency: Frequency,         // 繰り返しの頻度
+    pub interval: u32,                // 繰り返しの間隔
+    pub count: Option<u32>,           // 繰り返しの回数
+    pub until: Option<NaiveDateTime>, // 繰り返しの終了日時（ローカル時刻）
+    pub weekdays: Vec<u32>,           // 繰り返す曜日（0=日曜日、6=土曜日）
+}
+
+impl Recurrence {
+    // RRULEの値を解析（対応していない指定はエラー）
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let mut frequency = None;
+        let mut recurrence = Recurrence {
+            frequency: Frequency::Daily,
+            interval: 1,
+            count: None,
+            until: None,
+            weekdays: Vec::new(),
+        };
+        for part in value.split(';').filter(|part| !part.is_empty()) {
+            let (key, value) = part
+                .split_once('=')
+                .ok_or_else(|| format!("Invalid RRULE part: {}", part))?;
+            match key.to_ascii_uppercase().as_str() {
+                "FREQ" => {
+                    frequency = Some(match value.to_ascii_uppercase().as_str() {
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        "YEARLY" => Frequency::Yearly,
+                        other => return Err(format!("Unsupported RRULE frequency: {}", other)),
+                    })
+                }
+                "INTERVAL" => {
+                    recurrence.interval = value
+                        .parse::<u32>()
+                        .ok()
+                        .filter(|interval| *interval > 0)
+                        .ok_or_else(|| format!("Invalid RRULE interval: {}", value))?
+                }
+                "COUNT" => {
+                    recurrence.count = Some(
+                        value
+                            .parse()
+                            .map_err(|_| format!("Invalid RRULE count: {}", value))?,
+                    )
+                }
+                "UNTIL" => {
+                    let (until, _) = parse_ics_datetime(value)
+                        .ok_or_else(|| format!("Invalid RRULE until: {}", value))?;
+                    recurrence.until = Some(until);
+                }
+                "BYDAY" => {
+                    for day in value.split(',') {
+                        let weekday = ICS_WEEKDAYS
+                            .iter()
+                            .position(|code| code.eq_ignore_ascii_case(day))
+                            .ok_or_else(|| format!("Unsupported RRULE weekday: {}", day))?;
+                        recurrence.weekdays.push(weekday as u32);
+                    }
+                }
+                "WKST" => {}
+                other => return Err(format!("Unsupported RRULE part: {}", other)),
+            }
+        }
+        recurrence.frequency = frequency.ok_or("RRULE is missing FREQ")?;
+        Ok(recurrence)
+    }
+
+    // RRULEの値に変換
+    fn to_ics(&self) -> String {
+        let mut parts = vec![format!("FREQ={}", self.frequency.as_ics())];
+        if self.interval > 1 {
+            parts.push(format!("INTERVAL={}", self.interval));
+        }
+        if let Some(count) = self.count {
+            parts.push(format!("COUNT={}", count));
+        }
+        if let Some(until) = self.until {
+            parts.push(format!("UNTIL={}", format_ics_datetime(until)));
+        }
+        if !self.weekdays.is_empty() {
+            let days: Vec<&str> = self
+                .weekdays
+                .iter()
+                .filter_map(|day| ICS_WEEKDAYS.get(*day as usize).copied())
+                .collect();
+            parts.push(format!("BYDAY={}", days.join(",")));
+        }
+        parts.join(";")
+    }
+
+    // 開始日時からk番目の周期に含まれる日時を取得（日付の範囲を超える場合はNone）
+    fn period(&self, start: NaiveDateTime, k: u32) -> Option<Vec<NaiveDateTime>> {
+        let step = k.checked_mul(self.interval)?;
+        let time = start.time();
+        let date = start.date();
+        let dates: Vec<NaiveDate> = match self.frequency {
+            Frequency::Daily => {
+                let date = date.checked_add_signed(chrono::Duration::days(step as i64))?;
+                Some(date)
+                    .filter(|date| self.weekday_matches(*date))
+                    .into_iter()
+                    .collect()
+            }
+            Frequency::Weekly if self.weekdays.is_empty() => {
+                vec![date.checked_add_signed(chrono::Duration::weeks(step as i64))?]
+            }
+            Frequency::Weekly => {
+                // 週の始まりは月曜日
+                let monday = date.week(Weekday::Mon).first_day();
+                monday
+                    .checked_add_signed(chrono::Duration::weeks(step as i64))?
+                    .iter_days()
+                    .take(7)
+                    .filter(|date| self.weekday_matches(*date))
+                    .collect()
+            }
+            Frequency::Monthly => {
+                let months = date.month0().checked_add(step)?;
+                let year = date.year().checked_add(i32::try_from(months / 12).ok()?)?;
+                NaiveDate::from_ymd_opt(year, 1, 1)?;
+                // 存在しない日（31日など）の月は飛ばす
+                NaiveDate::from_ymd_opt(year, months % 12 + 1, date.day())
+                    .into_iter()
+                    .collect()
+            }
+            Frequency::Yearly => {
+                let year = date.year().checked_add(i32::try_from(step).ok()?)?;
+                NaiveDate::from_ymd_opt(year, 1, 1)?;
+                // 存在しない日（2月29日など）の年は飛ばす
+                NaiveDate::from_ymd_opt(year, date.month(), date.day())
+                    .into_iter()
+                    .collect()
+            }
+        };
+        let dates = dates
+            .into_iter()
+            .map(|date| date.and_time(time))
+            .filter(|at| *at >= start)
+            .collect();
+        Some(dates)
+    }
+
+    fn weekday_matches(&self, date: NaiveDate) -> bool {
+        self.weekdays.is_empty()
+            || self
+                .weekdays
+                .contains(&date.weekday().num_days_from_sunday())
+    }
+}
+
+/// iCalendarファイルから読み込んだアラーム
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CalendarAlarm {
+    pub uid: String,                    // 予定のUID
+    pub summary: String,                // 予定の件名
+    pub start: NaiveDateTime,           // 開始日時（ローカル時刻）
+    pub end: Option<NaiveDateTime>,     // 終了日時（終日の予定は終了日を含まない）
+    pub all_day: bool,                  // 終日の予定かどうか
+    pub recurrence: Option<Recurrence>, // 繰り返し
+    pub exdates: Vec<NaiveDateTime>,    // 繰り返しから除外する日時
+    pub reminder_minutes: Vec<i64>,     // 開始の何分前に知らせるか（VALARM）
+}
+
+/// 予定を読み込めなかった理由
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SkippedReason {
+    /// DTSTARTがない
+    MissingStart,
+    /// システムと異なるタイムゾーンが指定されている
+    ForeignTimeZone { tzid: String },
+    /// 対応していない繰り返し
+    UnsupportedRule { error: String },
+}
+
+/// 読み込めなかった予定
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SkippedEvent {
+    pub summary: String,              // 予定の件名
+    pub start: Option<NaiveDateTime>, // 開始日時（DTSTARTがない場合はNone）
+    pub reason: SkippedReason,        // 読み込めなかった理由
+}
+
+/// .icsファイルの読み込み結果
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ParsedCalendar {
+    pub alarms: Vec<CalendarAlarm>, // 読み込んだ予定
+    pub skipped: Vec<SkippedEvent>, // 読み込めなかった予定
+}
+
+impl CalendarAlarm {
+    // 開始日時を順に列挙
+    pub fn occurrences(&self) -> Box<dyn Iterator<Item = NaiveDateTime> + '_> {
+        let Some(rule) = &self.recurrence else {
+            return Box::new(std::iter::once(self.start));
+        };
+        let occurrences = (0..CALENDAR_MAX_PERIODS)
+            .map_while(move |k| rule.period(self.start, k))
+            .flatten()
+            .take_while(move |at| rule.until.is_none_or(|until| *at <= until))
+            .take(rule.count.map_or(usize::MAX, |count| count as usize))
+            .filter(move |at| !self.exdates.contains(at));
+        Box::new(occurrences)
+    }
+
+    // 指定した時刻より後で最初に鳴らすアラームまたは通知を取得
+    fn next_fire(&self, after: DateTime<Local>, ring_at_start: bool) -> Option<CalendarFire> {
+        let mut leads: Vec<(i64, CalendarFireKind)> = self
+            .reminder_minutes
+            .iter()
+            .map(|minutes| (*minutes, CalendarFireKind::Reminder(*minutes)))
+            .collect();
+        if ring_at_start && !self.all_day {
+            leads.push((0, CalendarFireKind::Alarm));
+        }
+        let max_lead = leads.iter().map(|(minutes, _)| *minutes).max()?;
+
+        let mut best: Option<CalendarFire> = None;
+        for start in self.occurrences() {
+            let Some(start) = Local.from_local_datetime(&start).earliest() else {
+                continue;
+            };
+            // これ以降の開始日時の通知は、見つかった通知より早くならない
+            if let Some(best) = &best {
+                if start - chrono::Duration::minutes(max_lead) > best.at {
+                    break;
+                }
+            }
+            for (minutes, kind) in &leads {
+                let at = start - chrono::Duration::minutes(*minutes);
+                if at > after && best.as_ref().is_none_or(|best| at < best.at) {
+                    best = Some(CalendarFire {
+                        at,
+                        summary: self.summary.clone(),
+                        kind: *kind,
+                    });
+                }
+            }
+        }
+        best
+    }
+}
+
+/// 予定のアラームと通知の種類
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CalendarFireKind {
+    Alarm,         // 開始時刻のアラーム
+    Reminder(i64), // 開始の指定分前の通知
+}
+
+/// 次に鳴らす予定のアラームまたは通知
+#[derive(Debug, Clone)]
+struct CalendarFire {
+    at: DateTime<Local>,
+    summary: String,
+    kind: CalendarFireKind,
+}
+
+// 読み込んだアラームのうち、指定した時刻より後で最初に鳴らすものを全て取得
+fn next_calendar_fires(
+    alarms: &[CalendarAlarm],
+    after: DateTime<Local>,
+    ring_at_start: bool,
+) -> Vec<CalendarFire> {
+    let fires: Vec<CalendarFire> = alarms
+        .iter()
+        .filter_map(|alarm| alarm.next_fire(after, ring_at_start))
+        .collect();
+    let Some(earliest) = fires.iter().map(|fire| fire.at).min() else {
+        return Vec::new();
+    };
+    fires
+        .into_iter()
+        .filter(|fire| fire.at == earliest)
+        .collect()
+}
+
+// iCalendarの内容から予定を読み込み（対応していない予定は理由とともに読み込めなかった予定にする）
+pub fn parse_calendar(content: &str) -> ParsedCalendar {
+    let mut parsed = ParsedCalendar::default();
+    let mut event: Option<CalendarAlarm> = None;
+    let mut rrule: Option<String> = None;
+    let mut tzid: Option<String> = None;
+    let mut triggers: Vec<String> = Vec::new();
+    let mut in_alarm = false;
+
+    for line in unfold_lines(content) {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let (name, params) = name.split_once(';').unwrap_or((name, ""));
+        match (name.to_ascii_uppercase().as_str(), value) {
+            ("BEGIN", "VEVENT") => {
+                event = Some(CalendarAlarm {
+                    uid: String::new(),
+                    summary: String::new(),
+                    start: NaiveDateTime::MIN,
+                    end: None,
+                    all_day: false,
+                    recurrence: None,
+                    exdates: Vec::new(),
+                    reminder_minutes: Vec::new(),
+                });
+                rrule = None;
+                tzid = None;
+                triggers.clear();
+            }
+            ("BEGIN", "VALARM") => in_alarm = true,
+            ("END", "VALARM") => in_alarm = false,
+            ("END", "VEVENT") => {
+                let Some(mut alarm) = event.take() else {
+                    continue;
+                };
+                let skipped_reason = if alarm.start == NaiveDateTime::MIN {
+                    Some(SkippedReason::MissingStart)
+                } else if let Some(tzid) = tzid.take().filter(|tzid| !is_local_zone(tzid)) {
+                    // タイムゾーンの変換には対応していないため、システムと異なるTZIDの予定は読み込まない
+                    Some(SkippedReason::ForeignTimeZone { tzid })
+                } else {
+                    match rrule
+                        .take()
+                        .map(|rrule| Recurrence::parse(&rrule))
+                        .transpose()
+                    {
+                        Ok(recurrence) => {
+                            alarm.recurrence = recurrence;
+                            None
+                        }
+                        Err(error) => Some(SkippedReason::UnsupportedRule { error }),
+                    }
+                };
+                if let Some(reason) = skipped_reason {
+                    parsed.skipped.push(SkippedEvent {
+                        summary: alarm.summary,
+                        start: (alarm.start != NaiveDateTime::MIN).then_some(alarm.start),
+                        reason,
+                    });
+                    continue;
+                }
+                alarm.reminder_minutes = triggers
+                    .iter()
+                    .filter_map(|trigger| parse_trigger(trigger, alarm.start))
+                    .collect();
+                alarm.reminder_minutes.sort_unstable();
+                alarm.reminder_minutes.dedup();
+                parsed.alarms.push(alarm);
+            }
+            (_, _) if event.is_none() => {}
+            ("TRIGGER", _) if in_alarm => triggers.push(format!("{};{}", params, value)),
+            (_, _) if in_alarm => {}
+            ("UID", _) => set_event(&mut event, |alarm| alarm.uid = unescape_text(value)),
+            ("SUMMARY", _) => set_event(&mut event, |alarm| alarm.summary = unescape_text(value)),
+            ("DTSTART", _) => {
+                tzid = tzid_param(params);
+                if let Some((start, all_day)) = parse_ics_datetime(value) {
+                    set_event(&mut event, |alarm| {
+                        alarm.start = start;
+                        alarm.all_day = all_day;
+                    });
+                }
+            }
+            ("DTEND", _) => {
+                if let Some((end, _)) = parse_ics_datetime(value) {
+                    set_event(&mut event, |alarm| alarm.end = Some(end));
+                }
+            }
+            ("RRULE", _) => rrule = Some(value.to_string()),
+            ("EXDATE", _) => set_event(&mut event, |alarm| {
+                alarm.exdates.extend(
+                    value
+                        .split(',')
+                        .filter_map(parse_ics_datetime)
+                        .map(|(at, _)| at),
+                )
+            }),
+            _ => {}
+        }
+    }
+    for skipped in &parsed.skipped {
+        eprintln!(
+            "Skipping calendar event {}: {:?}",
+            skipped.summary, skipped.reason
+        );
+    }
+    parsed
+}
+
+fn set_event(event: &mut Option<CalendarAlarm>, update: impl FnOnce(&mut CalendarAlarm)) {
+    if let Some(alarm) = event.as_mut() {
+        update(alarm);
+    }
+}
+
+// プロパティのパラメータからTZIDを取得
+fn tzid_param(params: &str) -> Option<String> {
+    params.split(';').find_map(|param| {
+        let (key, value) = param.split_once('=')?;
+        key.eq_ignore_ascii_case("TZID")
+            .then(|| value.trim_matches('"').to_string())
+    })
+}
+
+// TZIDがシステムのタイムゾーンと一致するかどうか（システムのタイムゾーンが取得できない場合は一致とみなす）
+fn is_local_zone(tzid: &str) -> bool {
+    match iana_time_zone::get_timezone() {
+        Ok(local) => iana_zone(&local).eq_ignore_ascii_case(iana_zone(tzid)),
+        Err(_) => true,
+    }
+}
+
+// Windowsのタイムゾーン名（Outlookなどが出力する）をIANAのタイムゾーン名に変換
+// 表にない名前はそのまま返す
+fn iana_zone(tzid: &str) -> &str {
+    WINDOWS_ZONES
+        .iter()
+        .find(|(windows, _)| windows.eq_ignore_ascii_case(tzid))
+        .map_or(tzid, |(_, iana)| iana)
+}
+
+// 折り返された行を連結
+fn unfold_lines(content: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in content.lines() {
+        let line = line.trim_end_matches('\r');
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+// テキストのエスケープを戻す
+fn unescape_text(value: &str) -> String {
+    value
+        .replace("\\n", "\n")
+        .replace("\\N", "\n")
+        .replace("\\,", ",")
+        .replace("\\;", ";")
+        .replace("\\\\", "\\")
+}
+
+// 日付・日時をローカル時刻として解析し、終日かどうかと共に返す
+// UTC（末尾がZ）はローカル時刻に変換し、それ以外はローカル時刻として扱う
+// （TZIDの指定はparse_calendarでシステムのタイムゾーンと一致するものだけを受け付ける）
+pub fn parse_ics_datetime(value: &str) -> Option<(NaiveDateTime, bool)> {
+    if let Some(utc) = value.strip_suffix('Z') {
+        let at = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+        let local = Utc
+            .from_utc_datetime(&at)
+            .with_timezone(&Local)
+            .naive_local();
+        return Some((local, false));
+    }
+    if let Ok(at) = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S") {
+        return Some((at, false));
+    }
+    let date = NaiveDate::parse_from_str(value, "%Y%m%d").ok()?;
+    Some((date.and_time(NaiveTime::MIN), true))
+}
+
+// VALARMのTRIGGERから開始の何分前に知らせるかを取得
+fn parse_trigger(trigger: &str, start: NaiveDateTime) -> Option<i64> {
+    let (params, value) = trigger.split_once(';')?;
+    if params.to_ascii_uppercase().contains("VALUE=DATE-TIME") {
+        let (at, _) = parse_ics_datetime(value)?;
+        return Some(start.signed_duration_since(at).num_minutes());
+    }
+    Some(-parse_duration_minutes(value)?)
+}
+
+// iCalendarの期間（例: -PT15M、P1DT2H）を分に変換
+fn parse_duration_minutes(value: &str) -> Option<i64> {
+    let (sign, rest) = match value.strip_prefix('-') {
+        Some(rest) => (-1, rest),
+        None => (1, value.strip_prefix('+').unwrap_or(value)),
+    };
+    let rest = rest.strip_prefix('P')?;
+    let mut minutes = 0;
+    let mut number = String::new();
+    for c in rest.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let unit = match c {
+            'W' => 7 * 24 * 60,
+            'D' => 24 * 60,
+            'H' => 60,
+            'M' => 1,
+            'S' => 0,
+            'T' => continue,
+            _ => return None,
+        };
+        minutes += number.parse::<i64>().ok()? * unit;
+        number.clear();
+    }
+    Some(sign * minutes)
+}
+
+// 予定をiCalendarの内容に変換（parse_calendarで読み込める形式）
+fn format_calendar(alarms: &[CalendarAlarm], stamp: DateTime<Utc>) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//VRChat Anywhere Alarm//EN".to_string(),
+    ];
+    for alarm in alarms {
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:{}", escape_text(&alarm.uid)));
+        lines.push(format!("DTSTAMP:{}", stamp.format("%Y%m%dT%H%M%SZ")));
+        lines.push(format!("SUMMARY:{}", escape_text(&alarm.summary)));
+        if alarm.all_day {
+            lines.push(format!(
+                "DTSTART;VALUE=DATE:{}",
+                alarm.start.format("%Y%m%d")
+            ));
+        } else {
+            lines.push(format!("DTSTART:{}", format_ics_datetime(alarm.start)));
+        }
+        match alarm.end {
+            Some(end) if alarm.all_day => {
+                lines.push(format!("DTEND;VALUE=DATE:{}", end.format("%Y%m%d")))
+            }
+            Some(end) => lines.push(format!("DTEND:{}", format_ics_datetime(end))),
+            None => {}
+        }
+        if let Some(recurrence) = &alarm.recurrence {
+            lines.push(format!("RRULE:{}", recurrence.to_ics()));
+        }
+        for exdate in &alarm.exdates {
+            lines.push(format!("EXDATE:{}", format_ics_datetime(*exdate)));
+        }
+        for minutes in &alarm.reminder_minutes {
+            lines.push("BEGIN:VALARM".to_string());
+            lines.push("ACTION:DISPLAY".to_string());
+            lines.push(format!("DESCRIPTION:{}", escape_text(&alarm.summary)));
+            lines.push(format!("TRIGGER:{}", format_duration_minutes(-minutes)));
+            lines.push("END:VALARM".to_string());
+        }
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+    lines
+        .iter()
+        .map(|line| fold_line(line))
+        .collect::<Vec<_>>()
+        .join("")
+}
+
+// 起床アラームを予定に変換（日の出・cron式によるアラームは次の1回のみ）
+fn wake_alarm_entry(
+    settings: &AlarmSettings,
+    alarm_hour: u32,
+    alarm_minute: u32,
+    next_alarm_at: Option<DateTime<Local>>,
+    today: NaiveDate,
+) -> Option<CalendarAlarm> {
+    let (start, recurrence) = match settings.alarm_type {
+        AlarmType::Fixed => {
+            // 曜日の指定がある場合は、今日以降で最初に鳴らす曜日を開始日にする
+            let first_date = today
+                .iter_days()
+                .take(7)
+                .find(|date| weekday_allowed(&settings.alarm_weekdays, *date))?;
+            let start = first_date.and_hms_opt(alarm_hour, alarm_minute, 0)?;
+            let recurrence = Recurrence {
+                frequency: if settings.alarm_weekdays.is_empty() {
+                    Frequency::Daily
+                } else {
+                    Frequency::Weekly
+                },
+                interval: 1,
+                count: None,
+                until: None,
+                weekdays: settings.alarm_weekdays.clone(),
+            };
+            (start, Some(recurrence))
+        }
+        AlarmType::Sunrise | AlarmType::Cron => (next_alarm_at?.naive_local(), None),
+    };
+
+    // スキップした日と休暇中の日を除外（過ぎた日は含めない）
+    let exdates = settings
+        .skip
+        .skip_date
+        .into_iter()
+        .chain(settings.skip.ranges().flat_map(|range| {
+            range
+                .start
+                .max(today)
+                .iter_days()
+                .take_while(|date| *date <= range.end)
+        }))
+        .filter(|date| *date >= start.date() && weekday_allowed(&settings.alarm_weekdays, *date))
+        .map(|date| date.and_time(start.time()))
+        .collect();
+
+    Some(CalendarAlarm {
+        uid: "wake-alarm@vrchat-anywhere-alarm".to_string(),
+        summary: "アラーム".to_string(),
+        start,
+        end: None,
+        all_day: false,
+        recurrence,
+        exdates,
+        reminder_minutes: Vec::new(),
+    })
+}
+
+// 起床アラームと読み込んだ予定を.icsファイルに書き出し、書き出した予定の数を返す
+pub fn export_calendar(state: &AppStateMutex, path: &str) -> Result<usize, String> {
+    let settings = load_settings();
+    let (alarm_on, alarm_hour, alarm_minute, next_alarm_at, imported) = {
+        let app_state = state
+            .lock()
+            .map_err(|e| format!("Failed to lock state: {}", e))?;
+        (
+            app_state.alarm_is_on,
+            vrc_float_to_hour(app_state.alarm_set_hour) as u32,
+            vrc_float_to_minute(app_state.alarm_set_minute) as u32,
+            app_state.next_alarm_at,
+            app_state.calendar_alarms.clone(),
+        )
+    };
+
+    let now = Local::now();
+    let wake_alarm = alarm_on
+        .then(|| {
+            wake_alarm_entry(
+                &settings,
+                alarm_hour,
+                alarm_minute,
+                next_alarm_at.map(|at| at.with_timezone(&Local)),
+                now.date_naive(),
+            )
+        })
+        .flatten();
+    let alarms: Vec<CalendarAlarm> = wake_alarm.into_iter().chain(imported).collect();
+
+    fs::write(path, format_calendar(&alarms, now.with_timezone(&Utc)))
+        .map_err(|e| format!("Failed to write calendar {}: {}", path, e))?;
+    println!("Exported {} events to {}", alarms.len(), path);
+    Ok(alarms.len())
+}
+
+// 日時をローカル時刻（タイムゾーンなし）の形式に変換
+fn format_ics_datetime(at: NaiveDateTime) -> String {
+    at.format("%Y%m%dT%H%M%S").to_string()
+}
+
+// 分をiCalendarの期間（例: -PT15M）に変換
+fn format_duration_minutes(minutes: i64) -> String {
+    let sign = if minutes < 0 { "-" } else { "" };
+    format!("{}PT{}M", sign, minutes.abs())
+}
+
+// テキストをエスケープ
+fn escape_text(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+// 75バイトを超える行を折り返し、改行（CRLF）を付ける
+fn fold_line(line: &str) -> String {
+    let mut folded = String::new();
+    let mut length = 0;
+    for c in line.chars() {
+        if length + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            length = 1;
+        }
+        folded.push(c);
+        length += c.len_utf8();
+    }
+    folded.push_str("\r\n");
+    folded
+}
+
+// 設定した.icsファイルを読み込み直し、内容が変わった場合はアラームを設定し直す
+// 読み込めなかった予定も結果に含める
+pub fn reload_calendar(
+    state: &AppStateMutex,
+    timer_manager: &TimerManagerMutex,
+) -> Result<ParsedCalendar, String> {
+    let settings = load_settings().calendar_import;
+    let parsed = if settings.path.is_empty() {
+        ParsedCalendar::default()
+    } else {
+        let content = fs::read_to_string(&settings.path)
+            .map_err(|e| format!("Failed to read calendar {}: {}", settings.path, e))?;
+        parse_calendar(&content)
+    };
+    let alarms = &parsed.alarms;
+
+    let changed = {
+        let mut app_state = state
+            .lock()
+            .map_err(|e| format!("Failed to lock state: {}", e))?;
+        let changed = app_state.calendar_alarms != *alarms;
+        app_state.calendar_alarms = alarms.clone();
+        changed
+    };
+    if changed {
+        println!("Calendar reloaded: {} events", alarms.len());
+        schedule_calendar(state.clone(), timer_manager.clone());
+    }
+    Ok(parsed)
+}
+
+// 読み込んだ予定のアラームと通知を設定（既存の設定は置き換える）
+pub fn schedule_calendar(state: AppStateMutex, timer_manager: TimerManagerMutex) {
+    let alarms = match state.lock() {
+        Ok(app_state) => app_state.calendar_alarms.clone(),
+        Err(e) => {
+            eprintln!("Failed to lock state: {}", e);
+            return;
+        }
+    };
+    let settings = load_settings().calendar_import;
+
+    let state_clone = state.clone();
+    let timer_manager_clone = timer_manager.clone();
+    let calendar_handle = tokio::spawn(async move {
+        let mut after = Local::now();
+        loop {
+            let fires = next_calendar_fires(&alarms, after, settings.ring_at_start);
+            let at = fires.first().map(|fire| fire.at);
+            if let Ok(mut app_state) = state_clone.lock() {
+                app_state.next_calendar_alarm_at = at.map(|at| at.with_timezone(&Utc));
+            }
+            let Some(at) = at else {
+                return;
+            };
+
+            let wait = at
+                .signed_duration_since(Local::now())
+                .to_std()
+                .unwrap_or_default();
+            sleep(wait).await;
+            after = at;
+            // 読み込み直しで取り消されないよう、アラームと通知は別のタスクで処理
+            for fire in fires {
+                tokio::spawn(fire_calendar(
+                    state_clone.clone(),
+                    timer_manager_clone.clone(),
+                    settings.clone(),
+                    fire,
+                ));
+            }
+        }
+    });
+    if let Ok(mut timer_mgr) = timer_manager.lock() {
+        timer_mgr.set_calendar_timer(calendar_handle);
+    }
+}
+
+// 設定した間隔で.icsファイルを読み込み直し、カレンダーの変更を反映
+pub async fn run_calendar_sync(state: AppStateMutex, timer_manager: TimerManagerMutex) {
+    loop {
+        if let Err(e) = reload_calendar(&state, &timer_manager) {
+            eprintln!("{}", e);
+        }
+        let settings = load_settings().calendar_import.clamped();
+        sleep(Duration::from_secs(settings.reload_minutes as u64 * 60)).await;
+    }
+}
+
+// 予定のアラームを鳴らすか、通知を送信
+async fn fire_calendar(
+    state: AppStateMutex,
+    timer_manager: TimerManagerMutex,
+    settings: CalendarImportSettings,
+    fire: CalendarFire,
+) {
+    match fire.kind {
+        CalendarFireKind::Alarm => {
+            let busy = match state.lock() {
+                Ok(mut app_state) => {
+                    // 鳴動中だけでなくスヌーズ中も、そのアラームの状態を上書きしないよう鳴らさない
+                    let busy = app_state.is_ringing || app_state.snooze_until.is_some();
+                    if !busy {
+                        app_state.alarm_source = AlarmSource::Calendar;
+                        app_state.snooze_count = 0;
+                        app_state.log_event(EventKind::AlarmFired, fire.summary.clone());
+                    }
+                    busy
+                }
+                Err(e) => {
+                    eprintln!("Failed to lock state: {}", e);
+                    return;
+                }
+            };
+            // 他のアラームが鳴動中・スヌーズ中の場合は重ねて鳴らさない
+            if busy {
+                println!("Calendar alarm while another alarm is ringing or snoozing. Skipping.");
+                return;
+            }
+            println!("Calendar alarm: {}", fire.summary);
+            handle_timer_event(state, timer_manager, TimerEvent::AlarmFire).await;
+        }
+        CalendarFireKind::Reminder(minutes) => {
+            println!("Calendar reminder: {} in {} minutes", fire.summary, minutes);
+            if settings.remind_chatbox {
+                let state_clone = state.clone();
+                let values = [
+                    ("summary", fire.summary.clone()),
+                    ("minutes", minutes.to_string()),
+                    ("time", fire.at.format("%H:%M").to_string()),
+                ];
+                tokio::spawn(async move {
+                    notify_chatbox(&state_clone, ChatboxEvent::CalendarReminder, &values).await;
+                });
+            }
+            if settings.remind_parameter {
+                send_reminder_pulse(&state).await;
+            }
+        }
+    }
+}
+
+// CalendarReminderパラメータを一定時間ONにする（おやすみ時間中は送信しない）
+async fn send_reminder_pulse(state: &AppStateMutex) {
+    let quiet_hours = load_settings().quiet_hours.clamped();
+    if quiet_hours.decide(false, Local::now()) != QuietDecision::Allow {
+        println!("Calendar reminder parameter dropped by quiet hours.");
+        return;
+    }
+    for active in [true, false] {
+        if let Err(e) = send_osc_to_vrchat(
+            "/avatar/parameters/CalendarReminder",
+            vec![OscType::Bool(active)],
+            state,
+        )
+        .await
+        {
+            eprintln!("Failed to send calendar reminder: {}", e);
+        }
+        if active {
+            sleep(CALENDAR_REMINDER_PULSE).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(year, month, day)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+    }
+
+    const WEEKLY_EVENT: &str = "BEGIN:VCALENDAR\r\n\
+BEGIN:VEVENT\r\n\
+UID:standup@example.com\r\n\
+SUMMARY:Stand\r\n up\\, daily\r\n\
+DTSTART:20261005T090000\r\n\
+RRULE:FREQ=WEEKLY;BYDAY=MO,WE;COUNT=5\r\n\
+EXDATE:20261007T090000\r\n\
+BEGIN:VALARM\r\n\
+ACTION:DISPLAY\r\n\
+TRIGGER:-PT15M\r\n\
+END:VALARM\r\n\
+BEGIN:VALARM\r\n\
+TRIGGER;RELATED=START:-P1DT2H\r\n\
+END:VALARM\r\n\
+END:VEVENT\r\n\
+END:VCALENDAR\r\n";
+
+    #[test]
+    fn parses_weekly_event_with_alarms() {
+        let alarms = parse_calendar(WEEKLY_EVENT).alarms;
+        assert_eq!(alarms.len(), 1);
+        let alarm = &alarms[0];
+        assert_eq!(alarm.summary, "Standup, daily");
+        assert_eq!(alarm.start, at(2026, 10, 5, 9, 0));
+        assert_eq!(alarm.reminder_minutes, vec![15, 26 * 60]);
+        // COUNTには除外した日も含まれる
+        assert_eq!(
+            alarm.occurrences().collect::<Vec<_>>(),
+            vec![
+                at(2026, 10, 5, 9, 0),
+                at(2026, 10, 12, 9, 0),
+                at(2026, 10, 14, 9, 0),
+                at(2026, 10, 19, 9, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn monthly_recurrence_skips_missing_days() {
+        let rule = Recurrence::parse("FREQ=MONTHLY;UNTIL=20270501T000000").unwrap();
+        let alarm = CalendarAlarm {
+            uid: String::new(),
+            summary: String::new(),
+            start: at(2027, 1, 31, 7, 0),
+            end: None,
+            all_day: false,
+            recurrence: Some(rule),
+            exdates: Vec::new(),
+            reminder_minutes: Vec::new(),
+        };
+        assert_eq!(
+            alarm.occurrences().collect::<Vec<_>>(),
+            vec![at(2027, 1, 31, 7, 0), at(2027, 3, 31, 7, 0)]
+        );
+    }
+
+    #[test]
+    fn huge_interval_stops_without_overflow() {
+        for rule in [
+            "FREQ=MONTHLY;INTERVAL=4294967295",
+            "FREQ=YEARLY;INTERVAL=4000000000",
+        ] {
+            let alarm = CalendarAlarm {
+                uid: String::new(),
+                summary: String::new(),
+                start: at(2027, 1, 31, 7, 0),
+                end: None,
+                all_day: false,
+                recurrence: Some(Recurrence::parse(rule).unwrap()),
+                exdates: Vec::new(),
+                reminder_minutes: Vec::new(),
+            };
+            assert_eq!(
+                alarm.occurrences().collect::<Vec<_>>(),
+                vec![at(2027, 1, 31, 7, 0)]
+            );
+        }
+    }
+
+    #[test]
+    fn skips_events_in_other_time_zones() {
+        let content = WEEKLY_EVENT.replace("DTSTART:", "DTSTART;TZID=Nowhere/Unknown:");
+        let parsed = parse_calendar(&content);
+        assert!(parsed.alarms.is_empty());
+        assert_eq!(
+            parsed.skipped,
+            vec![SkippedEvent {
+                summary: "Standup, daily".to_string(),
+                start: Some(at(2026, 10, 5, 9, 0)),
+                reason: SkippedReason::ForeignTimeZone {
+                    tzid: "Nowhere/Unknown".to_string()
+                },
+            }]
+        );
+    }
+
+    #[test]
+    fn maps_windows_time_zone_names() {
+        assert_eq!(iana_zone("Tokyo Standard Time"), "Asia/Tokyo");
+        assert_eq!(iana_zone("tokyo standard time"), "Asia/Tokyo");
+        assert_eq!(iana_zone("Europe/Paris"), "Europe/Paris");
+    }
+
+    #[test]
+    fn weekday_alarm_starts_on_first_allowed_day() {
+        let settings = AlarmSettings {
+            alarm_type: AlarmType::Fixed,
+            alarm_weekdays: vec![3, 5],
+            ..AlarmSettings::default()
+        };
+        // 2026-10-19は月曜日なので、最初は水曜日
+        let today = NaiveDate::from_ymd_opt(2026, 10, 19).unwrap();
+        let alarm = wake_alarm_entry(&settings, 7, 0, None, today).unwrap();
+        assert_eq!(alarm.start, at(2026, 10, 21, 7, 0));
+        let content = format_calendar(&[alarm], Utc::now());
+        assert!(content.contains("DTSTART:20261021T070000\r\n"));
+        assert!(content.contains("RRULE:FREQ=WEEKLY;BYDAY=WE,FR\r\n"));
+    }
+
+    #[test]
+    fn exported_calendar_round_trips() {
+        let parsed = parse_calendar(WEEKLY_EVENT);
+        let content = format_calendar(&parsed.alarms, Utc::now());
+        assert!(content.lines().all(|line| line.len() <= 76));
+        assert_eq!(parse_calendar(&content), parsed);
+    }
+
+    #[test]
+    fn rejects_unsupported_rules() {
+        assert!(Recurrence::parse("FREQ=HOURLY").is_err());
+        assert!(Recurrence::parse("FREQ=MONTHLY;BYDAY=1MO").is_err());
+        assert!(Recurrence::parse("INTERVAL=2").is_err());
+    }
+}
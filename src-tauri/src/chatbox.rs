@@ -0,0 +1,201 @@
+use crate::config::load_settings;
+use crate::osc::send_osc_to_vrchat;
+use crate::quiet::QuietDecision;
+use crate::types::AppStateMutex;
+use chrono::{Local, Utc};
+use rosc::OscType;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tokio::time::{sleep, Duration};
+
+// VRChatのチャットボックスに表示できる最大文字数
+const CHATBOX_MAX_CHARS: usize = 144;
+// VRChatのレート制限を避けるための最小送信間隔（秒）
+const CHATBOX_MIN_INTERVAL_SECONDS: u32 = 2;
+
+/// チャットボックスに通知するイベント
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChatboxEvent {
+    AlarmSoon,
+    AlarmFire,
+    Snoozed,
+    Stopped,
+    Challenge,
+    BedtimeReminder,
+    CalendarReminder,
+    TimerMissed,
+}
+
+impl ChatboxEvent {
+    // 鳴動中のアラームに伴う通知かどうか（アラームと同様におやすみ時間を無視できる）
+    fn accompanies_ringing(self) -> bool {
+        matches!(
+            self,
+            ChatboxEvent::AlarmFire
+                | ChatboxEvent::Snoozed
+                | ChatboxEvent::Stopped
+                | ChatboxEvent::Challenge
+        )
+    }
+}
+
+/// チャットボックス通知の設定
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChatboxSettings {
+    pub enabled: bool,  // チャットボックス通知を有効にするかどうか
+    pub locale: String, // 既定のテンプレートの言語（"ja" または "en"）
+    pub templates: HashMap<ChatboxEvent, String>, // イベントごとのテンプレート（既定を上書き）
+    pub min_interval_seconds: u32, // 送信の最小間隔
+    pub notify_before_minutes: u32, // アラーム前に通知する分数（0で無効）
+    pub play_sound: bool, // 通知音を鳴らすかどうか
+    pub show_typing: bool, // 送信前に入力中表示を出すかどうか
+}
+
+impl Default for ChatboxSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            locale: "ja".to_string(),
+            templates: HashMap::new(),
+            min_interval_seconds: 5,
+            notify_before_minutes: 10,
+            play_sound: false,
+            show_typing: true,
+        }
+    }
+}
+
+impl ChatboxSettings {
+    // 各値を有効範囲に丸め込み
+    pub fn clamped(self) -> Self {
+        Self {
+            min_interval_seconds: self
+                .min_interval_seconds
+                .clamp(CHATBOX_MIN_INTERVAL_SECONDS, 600),
+            notify_before_minutes: self.notify_before_minutes.min(120),
+            ..self
+        }
+    }
+
+    // イベントのテンプレートを取得（上書きがなければ言語ごとの既定値）
+    fn template(&self, event: ChatboxEvent) -> String {
+        if let Some(template) = self.templates.get(&event) {
+            return template.clone();
+        }
+        let template = match (self.locale.as_str(), event) {
+            ("en", ChatboxEvent::AlarmSoon) => "Alarm in {minutes} min",
+            ("en", ChatboxEvent::AlarmFire) => "Alarm! ({time})",
+            ("en", ChatboxEvent::Snoozed) => "Snoozed ({count}/{max})",
+            ("en", ChatboxEvent::Stopped) => "Good morning!",
+            ("en", ChatboxEvent::Challenge) => "Solve to stop: {problem}",
+            ("en", ChatboxEvent::BedtimeReminder) => "Time to get ready for bed ({time})",
+            ("en", ChatboxEvent::CalendarReminder) => "{summary} in {minutes} min ({time})",
+            ("en", ChatboxEvent::TimerMissed) => "{minutes} min timer ended during another alarm",
+            (_, ChatboxEvent::AlarmSoon) => "アラームまであと{minutes}分",
+            (_, ChatboxEvent::AlarmFire) => "アラーム！（{time}）",
+            (_, ChatboxEvent::Snoozed) => "スヌーズ中（{count}/{max}）",
+            (_, ChatboxEvent::Stopped) => "おはようございます！",
+            (_, ChatboxEvent::Challenge) => "解いて停止：{problem}",
+            (_, ChatboxEvent::BedtimeReminder) => "そろそろ寝る準備をしましょう（{time}就寝）",
+            (_, ChatboxEvent::CalendarReminder) => "{summary}まであと{minutes}分（{time}）",
+            (_, ChatboxEvent::TimerMissed) => "{minutes}分のタイマーが他のアラーム中に終了しました",
+        };
+        template.to_string()
+    }
+}
+
+// テンプレートのプレースホルダーを置換し、チャットボックスの文字数制限に収める
+fn render_template(template: &str, values: &[(&str, String)]) -> String {
+    let mut message = template.to_string();
+    for (key, value) in values {
+        message = message.replace(&format!("{{{}}}", key), value);
+    }
+    message.chars().take(CHATBOX_MAX_CHARS).collect()
+}
+
+// イベントをチャットボックスに通知
+pub async fn notify_chatbox(state: &AppStateMutex, event: ChatboxEvent, values: &[(&str, String)]) {
+    let all_settings = load_settings();
+    let settings = all_settings.chatbox.clamped();
+    if !settings.enabled {
+        return;
+    }
+    let message = render_template(&settings.template(event), values);
+    if message.is_empty() {
+        return;
+    }
+
+    // おやすみ時間中は重要なアラームに伴う通知以外を遅らせるか破棄する
+    let quiet_hours = all_settings.quiet_hours.clamped();
+    let from_wake_alarm = state
+        .lock()
+        .map(|app_state| app_state.alarm_source.is_wake())
+        .unwrap_or(true);
+    let critical =
+        quiet_hours.wake_alarm_critical && from_wake_alarm && event.accompanies_ringing();
+    match quiet_hours.decide(critical, Local::now()) {
+        QuietDecision::Allow => {}
+        QuietDecision::Defer(until) => {
+            if let Ok(wait) = until.signed_duration_since(Local::now()).to_std() {
+                sleep(wait).await;
+            }
+        }
+        QuietDecision::Drop => return,
+    }
+
+    // 前回の送信から最小間隔が経過するまで待機（送信枠を先に確保する）
+    let wait = {
+        let mut app_state = match state.lock() {
+            Ok(state) => state,
+            Err(e) => {
+                eprintln!("Failed to lock state: {}", e);
+                return;
+            }
+        };
+        let now = Utc::now();
+        let send_at = app_state
+            .last_chatbox_sent
+            .map(|last| last + chrono::Duration::seconds(settings.min_interval_seconds as i64))
+            .filter(|allowed_at| *allowed_at > now)
+            .unwrap_or(now);
+        app_state.last_chatbox_sent = Some(send_at);
+        send_at.signed_duration_since(now)
+    };
+    if let Ok(wait) = wait.to_std() {
+        sleep(wait).await;
+    }
+
+    if settings.show_typing {
+        if let Err(e) =
+            send_osc_to_vrchat("/chatbox/typing", vec![OscType::Bool(true)], state).await
+        {
+            eprintln!("Failed to send chatbox typing: {}", e);
+        }
+        sleep(Duration::from_millis(500)).await;
+    }
+
+    // 即時送信（キーボードを開かない）として送信
+    if let Err(e) = send_osc_to_vrchat(
+        "/chatbox/input",
+        vec![
+            OscType::String(message),
+            OscType::Bool(true),
+            OscType::Bool(settings.play_sound),
+        ],
+        state,
+    )
+    .await
+    {
+        eprintln!("Failed to send chatbox message: {}", e);
+    }
+
+    if settings.show_typing {
+        if let Err(e) =
+            send_osc_to_vrchat("/chatbox/typing", vec![OscType::Bool(false)], state).await
+        {
+            eprintln!("Failed to send chatbox typing: {}", e);
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use crate::osc::send_osc_bundle_to_vrchat;
+use crate::types::AppStateMutex;
+use crate::utils::{
+    day_to_vrc_float, hour_to_vrc_float, minute_to_vrc_float, now_in_timezone, second_to_vrc_float,
+    weekday_to_vrc_float,
+};
+use chrono::{DateTime, Datelike, FixedOffset, Timelike};
+use rosc::{OscMessage, OscType};
+use tokio::time::{sleep, Duration};
+
+// 時計パラメータを定期的にVRChatへ送信する
+pub async fn run_clock_sender(state: AppStateMutex) {
+    loop {
+        let (enabled, interval_seconds, offset_minutes) = match state.lock() {
+            Ok(app_state) => (
+                app_state.clock_enabled,
+                app_state.clock_interval_seconds,
+                app_state.timezone_offset_minutes,
+            ),
+            Err(e) => {
+                eprintln!("Failed to lock state: {}", e);
+                return;
+            }
+        };
+
+        let now = now_in_timezone(offset_minutes);
+        if enabled {
+            if let Err(e) = send_clock_parameters(&state, &now).await {
+                eprintln!("Failed to send clock parameters: {}", e);
+            }
+        }
+
+        // 次の送信タイミング（分の境界に揃える）まで待機
+        sleep(duration_until_next_tick(&now, interval_seconds)).await;
+    }
+}
+
+// 現在時刻を時計パラメータとしてVRChatに送信
+async fn send_clock_parameters(
+    state: &AppStateMutex,
+    now: &DateTime<FixedOffset>,
+) -> Result<(), String> {
+    let parameters = [
+        ("ClockHour", hour_to_vrc_float(now.hour() as i32)),
+        ("ClockMinute", minute_to_vrc_float(now.minute() as i32)),
+        ("ClockSecond", second_to_vrc_float(now.second() as i32)),
+        (
+            "ClockWeekday",
+            weekday_to_vrc_float(now.weekday().num_days_from_sunday() as i32),
+        ),
+        ("ClockDay", day_to_vrc_float(now.day() as i32)),
+    ];
+
+    let messages = parameters
+        .into_iter()
+        .map(|(name, value)| OscMessage {
+            addr: format!("/avatar/parameters/{}", name),
+            args: vec![OscType::Float(value)],
+        })
+        .collect();
+
+    send_osc_bundle_to_vrchat(messages, state).await
+}
+
+// 次の送信タイミングまでの待機時間を計算
+fn duration_until_next_tick(now: &DateTime<FixedOffset>, interval_seconds: u32) -> Duration {
+    let interval_ms = interval_seconds.clamp(1, 60) as u64 * 1000;
+    // うるう秒の場合もnanosecondが1秒未満になるよう丸め込み
+    let millis_into_minute =
+        now.second() as u64 * 1000 + (now.nanosecond() % 1_000_000_000) as u64 / 1_000_000;
+    let until_next_interval = interval_ms - millis_into_minute % interval_ms;
+    let until_next_minute = 60_000 - millis_into_minute;
+    // 境界を確実に越えるため少しだけ余分に待機
+    Duration::from_millis(until_next_interval.min(until_next_minute) + 5)
+}
@@ -1,8 +1,42 @@
+use crate::bedtime::BedtimeSettings;
+use crate::calendar::{
+    export_calendar, reload_calendar, schedule_calendar, CalendarAlarm, CalendarImportSettings,
+    ParsedCalendar,
+};
+use crate::chatbox::ChatboxSettings;
 use crate::config::{load_settings, save_settings};
+use crate::countdown::{cancel_countdown, start_countdown, CountdownSettings};
+use crate::cron::CronAlarmSettings;
+use crate::dismiss::{ChallengeSettings, MovementDismissSettings};
+use crate::escalation::EscalationSettings;
+use crate::holiday::HolidaySettings;
+use crate::nap::{self, start_nap, NapPreset, NapSettings, WakeTimeSuggestion};
 use crate::osc::send_osc_to_vrchat;
-use crate::types::{AlarmSettings, AppState, AppStateMutex};
-use crate::utils::{hour_to_vrc_float, minute_to_vrc_float};
-use chrono::Utc;
+use crate::pomodoro::{
+    apply_pomodoro_action, PomodoroAction, PomodoroSettings, PomodoroState,
+};
+use crate::quiet::QuietHoursSettings;
+use crate::schedule::normalize_weekdays;
+use crate::skip::{self, SkipSettings, VacationRange};
+use crate::sleep::{
+    export_session, finish_session, load_reports, save_finished_session, start_session,
+    SleepReport,
+};
+use crate::presence::PresenceSettings;
+use crate::smart_wake::SmartWakeSettings;
+use crate::snooze::SnoozeStrategy;
+use crate::solar::SolarAlarmSettings;
+use crate::stopwatch::StopwatchLap;
+use crate::timer::{next_fire_info, reschedule_if_idle};
+use crate::types::{
+    AlarmSettings, AlarmSource, AlarmType, AppState, AppStateMutex, EventLogEntry, NextFireInfo,
+    RampCurve, TimerManagerMutex,
+};
+use crate::utils::{
+    clamp_max_snoozes, clamp_ringing_duration_minutes, clamp_snooze_duration_minutes,
+    hour_to_vrc_float, minute_to_vrc_float,
+};
+use chrono::{DateTime, Local, NaiveDate, NaiveTime, Utc};
 use rosc::{OscMessage, OscPacket, OscType};
 use serde::{Deserialize, Serialize};
 use std::net::SocketAddr;
@@ -185,9 +219,7 @@ pub async fn save_alarm_settings(
         // 分を有効範囲に丸め込み
         alarm_minute: alarm_minute.clamp(0, 59),
         alarm_is_on,
-        max_snoozes: current_settings.max_snoozes,
-        ringing_duration_minutes: current_settings.ringing_duration_minutes,
-        snooze_duration_minutes: current_settings.snooze_duration_minutes,
+        ..current_settings
     };
 
     save_settings(&settings)?;
@@ -238,13 +270,11 @@ pub async fn save_timer_settings(
     // 現在の設定を取得し、タイマー設定を更新
     let current_settings = load_settings();
     let settings = AlarmSettings {
-        alarm_hour: current_settings.alarm_hour,
-        alarm_minute: current_settings.alarm_minute,
-        alarm_is_on: current_settings.alarm_is_on,
         // 各設定を有効範囲に丸め込み
-        max_snoozes: max_snoozes.clamp(1, 20),
-        ringing_duration_minutes: ringing_duration_minutes.clamp(1, 60),
-        snooze_duration_minutes: snooze_duration_minutes.clamp(1, 30),
+        max_snoozes: clamp_max_snoozes(max_snoozes),
+        ringing_duration_minutes: clamp_ringing_duration_minutes(ringing_duration_minutes),
+        snooze_duration_minutes: clamp_snooze_duration_minutes(snooze_duration_minutes),
+        ..current_settings
     };
 
     save_settings(&settings)?;
@@ -273,6 +303,744 @@ pub fn get_timer_settings(_state: tauri::State<AppStateMutex>) -> Result<(u32, u
     ))
 }
 
+// 残り時間パラメータの送信設定を保存
+#[tauri::command]
+pub fn save_countdown_settings(send_countdown_parameters: bool) -> Result<(), String> {
+    let settings = AlarmSettings {
+        send_countdown_parameters,
+        ..load_settings()
+    };
+    save_settings(&settings)
+}
+
+// 次回アラームとスヌーズ再発火の時刻を取得
+#[tauri::command]
+pub fn get_next_fire_times(state: tauri::State<AppStateMutex>) -> Result<NextFireInfo, String> {
+    next_fire_info(&state)
+}
+
+// 時計パラメータの設定を保存
+#[tauri::command]
+pub fn save_clock_settings(
+    clock_enabled: bool,
+    clock_interval_seconds: u32,
+    timezone_offset_minutes: Option<i32>,
+    state: tauri::State<AppStateMutex>,
+) -> Result<(), String> {
+    let settings = AlarmSettings {
+        clock_enabled,
+        // 送信間隔を有効範囲に丸め込み
+        clock_interval_seconds: clock_interval_seconds.clamp(1, 60),
+        // タイムゾーンをUTC-12:00からUTC+14:00の範囲に丸め込み
+        timezone_offset_minutes: timezone_offset_minutes.map(|offset| offset.clamp(-720, 840)),
+        ..load_settings()
+    };
+
+    save_settings(&settings)?;
+
+    // アプリ状態を更新
+    let mut app_state = state
+        .lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    app_state.clock_enabled = settings.clock_enabled;
+    app_state.clock_interval_seconds = settings.clock_interval_seconds;
+    app_state.timezone_offset_minutes = settings.timezone_offset_minutes;
+
+    Ok(())
+}
+
+// 時計パラメータの設定を取得
+#[tauri::command]
+pub fn get_clock_settings() -> Result<(bool, u32, Option<i32>), String> {
+    let settings = load_settings();
+    Ok((
+        settings.clock_enabled,
+        settings.clock_interval_seconds,
+        settings.timezone_offset_minutes,
+    ))
+}
+
+// サンライズの設定を保存
+#[tauri::command]
+pub async fn save_sunrise_settings(
+    sunrise_enabled: bool,
+    sunrise_minutes: u32,
+    sunrise_curve: RampCurve,
+    state: tauri::State<'_, AppStateMutex>,
+    timer_manager: tauri::State<'_, TimerManagerMutex>,
+) -> Result<(), String> {
+    let settings = AlarmSettings {
+        sunrise_enabled,
+        // 開始時間を有効範囲に丸め込み
+        sunrise_minutes: sunrise_minutes.clamp(1, 120),
+        sunrise_curve,
+        ..load_settings()
+    };
+    save_settings(&settings)?;
+
+    // 待機中のアラームに反映
+    reschedule_if_idle(state.inner().clone(), timer_manager.inner().clone()).await;
+    Ok(())
+}
+
+// サンライズの設定を取得
+#[tauri::command]
+pub fn get_sunrise_settings() -> Result<(bool, u32, RampCurve), String> {
+    let settings = load_settings();
+    Ok((
+        settings.sunrise_enabled,
+        settings.sunrise_minutes,
+        settings.sunrise_curve,
+    ))
+}
+
+// アラームの強さの段階的な上昇設定を保存
+#[tauri::command]
+pub fn save_escalation_settings(escalation: EscalationSettings) -> Result<(), String> {
+    let settings = AlarmSettings {
+        // 各値を有効範囲に丸め込み
+        escalation: escalation.clamped(),
+        ..load_settings()
+    };
+    save_settings(&settings)
+}
+
+// アラームの強さの段階的な上昇設定を取得
+#[tauri::command]
+pub fn get_escalation_settings() -> Result<EscalationSettings, String> {
+    Ok(load_settings().escalation)
+}
+
+// スヌーズ間隔の決め方を保存
+#[tauri::command]
+pub fn save_snooze_strategy(snooze_strategy: SnoozeStrategy) -> Result<(), String> {
+    let snooze_strategy = snooze_strategy.clamped();
+    snooze_strategy.validate()?;
+    let settings = AlarmSettings {
+        snooze_strategy,
+        ..load_settings()
+    };
+    save_settings(&settings)
+}
+
+// スヌーズ間隔の決め方を取得
+#[tauri::command]
+pub fn get_snooze_strategy() -> Result<SnoozeStrategy, String> {
+    Ok(load_settings().snooze_strategy)
+}
+
+// チャットボックス通知の設定を保存
+#[tauri::command]
+pub async fn save_chatbox_settings(
+    chatbox: ChatboxSettings,
+    state: tauri::State<'_, AppStateMutex>,
+    timer_manager: tauri::State<'_, TimerManagerMutex>,
+) -> Result<(), String> {
+    let settings = AlarmSettings {
+        // 送信間隔などを有効範囲に丸め込み
+        chatbox: chatbox.clamped(),
+        ..load_settings()
+    };
+    save_settings(&settings)?;
+
+    // 待機中のアラームの予告通知に反映
+    reschedule_if_idle(state.inner().clone(), timer_manager.inner().clone()).await;
+    Ok(())
+}
+
+// チャットボックス通知の設定を取得
+#[tauri::command]
+pub fn get_chatbox_settings() -> Result<ChatboxSettings, String> {
+    Ok(load_settings().chatbox)
+}
+
+// 停止チャレンジの設定を保存
+#[tauri::command]
+pub fn save_challenge_settings(challenge: ChallengeSettings) -> Result<(), String> {
+    let settings = AlarmSettings {
+        challenge,
+        ..load_settings()
+    };
+    save_settings(&settings)
+}
+
+// 停止チャレンジの設定を取得
+#[tauri::command]
+pub fn get_challenge_settings() -> Result<ChallengeSettings, String> {
+    Ok(load_settings().challenge)
+}
+
+// 動きによる停止の設定を保存
+#[tauri::command]
+pub fn save_movement_dismiss_settings(
+    movement_dismiss: MovementDismissSettings,
+) -> Result<(), String> {
+    let settings = AlarmSettings {
+        // しきい値を有効範囲に丸め込み
+        movement_dismiss: movement_dismiss.clamped(),
+        ..load_settings()
+    };
+    save_settings(&settings)
+}
+
+// 動きによる停止の設定を取得
+#[tauri::command]
+pub fn get_movement_dismiss_settings() -> Result<MovementDismissSettings, String> {
+    Ok(load_settings().movement_dismiss)
+}
+
+// スマート起床の設定を保存
+#[tauri::command]
+pub async fn save_smart_wake_settings(
+    smart_wake: SmartWakeSettings,
+    state: tauri::State<'_, AppStateMutex>,
+    timer_manager: tauri::State<'_, TimerManagerMutex>,
+) -> Result<(), String> {
+    let settings = AlarmSettings {
+        // 検知時間帯としきい値を有効範囲に丸め込み
+        smart_wake: smart_wake.clamped(),
+        ..load_settings()
+    };
+    save_settings(&settings)?;
+
+    // 待機中のアラームに反映
+    reschedule_if_idle(state.inner().clone(), timer_manager.inner().clone()).await;
+    Ok(())
+}
+
+// スマート起床の設定を取得
+#[tauri::command]
+pub fn get_smart_wake_settings() -> Result<SmartWakeSettings, String> {
+    Ok(load_settings().smart_wake)
+}
+
+// アラームの種類ごとのAFK・VRモードに応じた動作の設定を保存
+#[tauri::command]
+pub fn save_presence_rules(presence: PresenceSettings) -> Result<(), String> {
+    let settings = AlarmSettings {
+        presence,
+        ..load_settings()
+    };
+    save_settings(&settings)
+}
+
+// アラームの種類ごとのAFK・VRモードに応じた動作の設定を取得
+#[tauri::command]
+pub fn get_presence_rules() -> Result<PresenceSettings, String> {
+    Ok(load_settings().presence)
+}
+
+// おやすみ時間の設定を保存
+#[tauri::command]
+pub fn save_quiet_hours(quiet_hours: QuietHoursSettings) -> Result<(), String> {
+    let settings = AlarmSettings {
+        // 時刻を有効範囲に丸め込み
+        quiet_hours: quiet_hours.clamped(),
+        ..load_settings()
+    };
+    save_settings(&settings)
+}
+
+// おやすみ時間の設定を取得
+#[tauri::command]
+pub fn get_quiet_hours() -> Result<QuietHoursSettings, String> {
+    Ok(load_settings().quiet_hours)
+}
+
+// カウントダウンタイマーを開始し、終了時刻を返す
+#[tauri::command]
+pub async fn start_countdown_timer(
+    minutes: u32,
+    state: tauri::State<'_, AppStateMutex>,
+    timer_manager: tauri::State<'_, TimerManagerMutex>,
+) -> Result<DateTime<Utc>, String> {
+    start_countdown(
+        state.inner().clone(),
+        timer_manager.inner().clone(),
+        minutes,
+        AlarmSource::Countdown,
+    )
+    .await
+}
+
+// カウントダウンタイマーをキャンセル
+#[tauri::command]
+pub async fn cancel_countdown_timer(
+    state: tauri::State<'_, AppStateMutex>,
+    timer_manager: tauri::State<'_, TimerManagerMutex>,
+) -> Result<(), String> {
+    cancel_countdown(state.inner().clone(), timer_manager.inner().clone()).await;
+    Ok(())
+}
+
+// カウントダウンタイマーの設定を保存
+#[tauri::command]
+pub fn save_countdown_timer_settings(countdown_timer: CountdownSettings) -> Result<(), String> {
+    let settings = AlarmSettings {
+        // プリセットを有効範囲に丸め込み
+        countdown_timer: countdown_timer.clamped(),
+        ..load_settings()
+    };
+    save_settings(&settings)
+}
+
+// カウントダウンタイマーの設定を取得
+#[tauri::command]
+pub fn get_countdown_timer_settings() -> Result<CountdownSettings, String> {
+    Ok(load_settings().countdown_timer)
+}
+
+// ストップウォッチのラップの履歴を取得
+#[tauri::command]
+pub fn get_stopwatch_laps(
+    state: tauri::State<'_, AppStateMutex>,
+) -> Result<Vec<StopwatchLap>, String> {
+    let app_state = state
+        .lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    Ok(app_state.stopwatch.laps.clone())
+}
+
+// ポモドーロを開始（動作中の場合は最初からやり直す）
+#[tauri::command]
+pub async fn start_pomodoro(
+    state: tauri::State<'_, AppStateMutex>,
+    timer_manager: tauri::State<'_, TimerManagerMutex>,
+) -> Result<PomodoroState, String> {
+    apply_pomodoro_action(
+        state.inner().clone(),
+        timer_manager.inner().clone(),
+        PomodoroAction::Start,
+    )
+    .await
+}
+
+// ポモドーロを停止
+#[tauri::command]
+pub async fn stop_pomodoro(
+    state: tauri::State<'_, AppStateMutex>,
+    timer_manager: tauri::State<'_, TimerManagerMutex>,
+) -> Result<PomodoroState, String> {
+    apply_pomodoro_action(
+        state.inner().clone(),
+        timer_manager.inner().clone(),
+        PomodoroAction::Stop,
+    )
+    .await
+}
+
+// ポモドーロの現在のフェーズを終了し、次のフェーズに進む
+#[tauri::command]
+pub async fn skip_pomodoro_phase(
+    state: tauri::State<'_, AppStateMutex>,
+    timer_manager: tauri::State<'_, TimerManagerMutex>,
+) -> Result<PomodoroState, String> {
+    apply_pomodoro_action(
+        state.inner().clone(),
+        timer_manager.inner().clone(),
+        PomodoroAction::Skip,
+    )
+    .await
+}
+
+// ポモドーロの現在の状態を取得
+#[tauri::command]
+pub fn get_pomodoro_status(
+    state: tauri::State<'_, AppStateMutex>,
+) -> Result<PomodoroState, String> {
+    let app_state = state
+        .lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    Ok(app_state.pomodoro.clone())
+}
+
+// ポモドーロの設定を保存（動作中のサイクルには次回の開始時から反映）
+#[tauri::command]
+pub fn save_pomodoro_settings(pomodoro: PomodoroSettings) -> Result<(), String> {
+    let settings = AlarmSettings {
+        // 各時間を有効範囲に丸め込み
+        pomodoro: pomodoro.clamped(),
+        ..load_settings()
+    };
+    save_settings(&settings)
+}
+
+// ポモドーロの設定を取得
+#[tauri::command]
+pub fn get_pomodoro_settings() -> Result<PomodoroSettings, String> {
+    Ok(load_settings().pomodoro)
+}
+
+// 仮眠を開始し、起床時刻を返す（プリセット未指定の場合は既定のプリセット）
+#[tauri::command]
+pub async fn start_nap_alarm(
+    preset: Option<NapPreset>,
+    state: tauri::State<'_, AppStateMutex>,
+    timer_manager: tauri::State<'_, TimerManagerMutex>,
+) -> Result<DateTime<Utc>, String> {
+    let settings = load_settings().nap.clamped();
+    start_nap(
+        state.inner().clone(),
+        timer_manager.inner().clone(),
+        &settings,
+        preset,
+    )
+    .await
+}
+
+// 就寝時刻（HH:MM、未指定の場合は現在時刻）から睡眠サイクルに合わせた起床時刻を提案
+#[tauri::command]
+pub fn suggest_wake_times(bedtime: Option<String>) -> Result<Vec<WakeTimeSuggestion>, String> {
+    let bedtime = match bedtime {
+        Some(bedtime) => NaiveTime::parse_from_str(&bedtime, "%H:%M")
+            .map_err(|e| format!("Invalid bedtime '{}': {}", bedtime, e))?,
+        None => Local::now().time(),
+    };
+    let settings = load_settings().nap.clamped();
+    Ok(nap::suggest_wake_times(bedtime, settings.fall_asleep_minutes))
+}
+
+// 仮眠の設定を保存
+#[tauri::command]
+pub fn save_nap_settings(nap: NapSettings) -> Result<(), String> {
+    let settings = AlarmSettings {
+        // 分数を有効範囲に丸め込み
+        nap: nap.clamped(),
+        ..load_settings()
+    };
+    save_settings(&settings)
+}
+
+// 仮眠の設定を取得
+#[tauri::command]
+pub fn get_nap_settings() -> Result<NapSettings, String> {
+    Ok(load_settings().nap)
+}
+
+// アラームを鳴らす曜日を保存（0=日曜日、空の場合は毎日）
+#[tauri::command]
+pub async fn save_alarm_weekdays(
+    weekdays: Vec<u32>,
+    state: tauri::State<'_, AppStateMutex>,
+    timer_manager: tauri::State<'_, TimerManagerMutex>,
+) -> Result<(), String> {
+    let settings = AlarmSettings {
+        alarm_weekdays: normalize_weekdays(weekdays),
+        ..load_settings()
+    };
+    save_settings(&settings)?;
+
+    // 待機中のアラームに反映
+    reschedule_if_idle(state.inner().clone(), timer_manager.inner().clone()).await;
+    Ok(())
+}
+
+// アラームを鳴らす曜日を取得
+#[tauri::command]
+pub fn get_alarm_weekdays() -> Result<Vec<u32>, String> {
+    Ok(load_settings().alarm_weekdays)
+}
+
+// アラーム時刻の決め方を保存
+#[tauri::command]
+pub async fn save_alarm_type(
+    alarm_type: AlarmType,
+    state: tauri::State<'_, AppStateMutex>,
+    timer_manager: tauri::State<'_, TimerManagerMutex>,
+) -> Result<(), String> {
+    let current = load_settings();
+    // cron式が正しくない場合は切り替えない
+    if alarm_type == AlarmType::Cron {
+        current.cron_alarm.validate()?;
+    }
+    let settings = AlarmSettings {
+        alarm_type,
+        ..current
+    };
+    save_settings(&settings)?;
+
+    // 待機中のアラームに反映
+    reschedule_if_idle(state.inner().clone(), timer_manager.inner().clone()).await;
+    Ok(())
+}
+
+// アラーム時刻の決め方を取得
+#[tauri::command]
+pub fn get_alarm_type() -> Result<AlarmType, String> {
+    Ok(load_settings().alarm_type)
+}
+
+// 日の出を基準にしたアラームの設定を保存
+#[tauri::command]
+pub async fn save_solar_alarm_settings(
+    solar_alarm: SolarAlarmSettings,
+    state: tauri::State<'_, AppStateMutex>,
+    timer_manager: tauri::State<'_, TimerManagerMutex>,
+) -> Result<(), String> {
+    // 座標と時間帯が正しいか検証してから保存
+    solar_alarm.validate()?;
+    let settings = AlarmSettings {
+        solar_alarm: solar_alarm.clamped(),
+        ..load_settings()
+    };
+    save_settings(&settings)?;
+
+    // 待機中のアラームに反映
+    reschedule_if_idle(state.inner().clone(), timer_manager.inner().clone()).await;
+    Ok(())
+}
+
+// 日の出を基準にしたアラームの設定を取得
+#[tauri::command]
+pub fn get_solar_alarm_settings() -> Result<SolarAlarmSettings, String> {
+    Ok(load_settings().solar_alarm)
+}
+
+// cron式によるアラームの設定を保存
+#[tauri::command]
+pub async fn save_cron_alarm_settings(
+    cron_alarm: CronAlarmSettings,
+    state: tauri::State<'_, AppStateMutex>,
+    timer_manager: tauri::State<'_, TimerManagerMutex>,
+) -> Result<(), String> {
+    // cron式が正しいか検証してから保存
+    let cron_alarm = cron_alarm.clamped();
+    cron_alarm.validate()?;
+    let settings = AlarmSettings {
+        cron_alarm,
+        ..load_settings()
+    };
+    save_settings(&settings)?;
+
+    // 待機中のアラームに反映
+    reschedule_if_idle(state.inner().clone(), timer_manager.inner().clone()).await;
+    Ok(())
+}
+
+// cron式によるアラームの設定を取得
+#[tauri::command]
+pub fn get_cron_alarm_settings() -> Result<CronAlarmSettings, String> {
+    Ok(load_settings().cron_alarm)
+}
+
+// 祝日の設定を保存
+#[tauri::command]
+pub async fn save_holiday_settings(
+    holiday: HolidaySettings,
+    state: tauri::State<'_, AppStateMutex>,
+    timer_manager: tauri::State<'_, TimerManagerMutex>,
+) -> Result<(), String> {
+    let settings = AlarmSettings {
+        holiday: holiday.clamped(),
+        ..load_settings()
+    };
+    save_settings(&settings)?;
+
+    // 待機中のアラームに反映
+    reschedule_if_idle(state.inner().clone(), timer_manager.inner().clone()).await;
+    Ok(())
+}
+
+// 祝日の設定を取得
+#[tauri::command]
+pub fn get_holiday_settings() -> Result<HolidaySettings, String> {
+    Ok(load_settings().holiday)
+}
+
+// 次のアラームを1回スキップし、スキップした日を返す
+#[tauri::command]
+pub async fn skip_next_alarm(
+    state: tauri::State<'_, AppStateMutex>,
+    timer_manager: tauri::State<'_, TimerManagerMutex>,
+) -> Result<NaiveDate, String> {
+    skip::skip_next_alarm(state.inner().clone(), timer_manager.inner().clone()).await
+}
+
+// 次のアラームのスキップを取り消し
+#[tauri::command]
+pub async fn cancel_skip_next_alarm(
+    state: tauri::State<'_, AppStateMutex>,
+    timer_manager: tauri::State<'_, TimerManagerMutex>,
+) -> Result<(), String> {
+    skip::cancel_skip_next_alarm(state.inner().clone(), timer_manager.inner().clone()).await
+}
+
+// 休暇の期間を追加
+#[tauri::command]
+pub async fn add_vacation(
+    start: NaiveDate,
+    end: NaiveDate,
+    state: tauri::State<'_, AppStateMutex>,
+    timer_manager: tauri::State<'_, TimerManagerMutex>,
+) -> Result<(), String> {
+    let range = VacationRange { start, end };
+    skip::add_vacation(state.inner().clone(), timer_manager.inner().clone(), range).await
+}
+
+// 休暇の期間を削除
+#[tauri::command]
+pub async fn remove_vacation(
+    start: NaiveDate,
+    end: NaiveDate,
+    state: tauri::State<'_, AppStateMutex>,
+    timer_manager: tauri::State<'_, TimerManagerMutex>,
+) -> Result<(), String> {
+    let range = VacationRange { start, end };
+    skip::remove_vacation(state.inner().clone(), timer_manager.inner().clone(), range).await
+}
+
+// .icsファイルを読み込み、以降は設定した間隔で読み込み直す（読み込めなかった予定も返す）
+#[tauri::command]
+pub async fn import_calendar(
+    path: String,
+    state: tauri::State<'_, AppStateMutex>,
+    timer_manager: tauri::State<'_, TimerManagerMutex>,
+) -> Result<ParsedCalendar, String> {
+    let current = load_settings();
+    let settings = AlarmSettings {
+        calendar_import: CalendarImportSettings {
+            path,
+            ..current.calendar_import.clone()
+        },
+        ..current
+    };
+    save_settings(&settings)?;
+    reload_calendar(state.inner(), timer_manager.inner())
+}
+
+// 設定した.icsファイルをすぐに読み込み直す
+#[tauri::command]
+pub async fn reload_calendar_now(
+    state: tauri::State<'_, AppStateMutex>,
+    timer_manager: tauri::State<'_, TimerManagerMutex>,
+) -> Result<ParsedCalendar, String> {
+    reload_calendar(state.inner(), timer_manager.inner())
+}
+
+// 読み込んだ予定のアラームを取得
+#[tauri::command]
+pub fn get_imported_alarms(
+    state: tauri::State<AppStateMutex>,
+) -> Result<Vec<CalendarAlarm>, String> {
+    let app_state = state
+        .lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    Ok(app_state.calendar_alarms.clone())
+}
+
+// .icsファイルの読み込みの設定を保存（空のパスで読み込みを解除）
+#[tauri::command]
+pub async fn save_calendar_import_settings(
+    calendar_import: CalendarImportSettings,
+    state: tauri::State<'_, AppStateMutex>,
+    timer_manager: tauri::State<'_, TimerManagerMutex>,
+) -> Result<(), String> {
+    let settings = AlarmSettings {
+        calendar_import: calendar_import.clamped(),
+        ..load_settings()
+    };
+    save_settings(&settings)?;
+
+    // 鳴らし方の変更も反映するため、内容が同じでも設定し直す
+    reload_calendar(state.inner(), timer_manager.inner())?;
+    schedule_calendar(state.inner().clone(), timer_manager.inner().clone());
+    Ok(())
+}
+
+// 起床アラームと読み込んだ予定を.icsファイルに書き出し、書き出した予定の数を返す
+#[tauri::command]
+pub fn export_alarm_calendar(
+    path: String,
+    state: tauri::State<AppStateMutex>,
+) -> Result<usize, String> {
+    export_calendar(state.inner(), &path)
+}
+
+// .icsファイルの読み込みの設定を取得
+#[tauri::command]
+pub fn get_calendar_import_settings() -> Result<CalendarImportSettings, String> {
+    Ok(load_settings().calendar_import)
+}
+
+// 次回のスキップと休暇の設定を取得
+#[tauri::command]
+pub fn get_skip_settings() -> Result<SkipSettings, String> {
+    Ok(load_settings().skip)
+}
+
+// 就寝リマインダーの設定を保存
+#[tauri::command]
+pub async fn save_bedtime_settings(
+    bedtime: BedtimeSettings,
+    state: tauri::State<'_, AppStateMutex>,
+    timer_manager: tauri::State<'_, TimerManagerMutex>,
+) -> Result<(), String> {
+    let settings = AlarmSettings {
+        // 睡眠時間とリマインダーの分数を有効範囲に丸め込み
+        bedtime: bedtime.clamped(),
+        ..load_settings()
+    };
+    save_settings(&settings)?;
+
+    // 待機中のアラームに反映
+    reschedule_if_idle(state.inner().clone(), timer_manager.inner().clone()).await;
+    Ok(())
+}
+
+// 就寝リマインダーの設定を取得
+#[tauri::command]
+pub fn get_bedtime_settings() -> Result<BedtimeSettings, String> {
+    Ok(load_settings().bedtime)
+}
+
+// イベント履歴を取得
+#[tauri::command]
+pub fn get_event_log(state: tauri::State<AppStateMutex>) -> Result<Vec<EventLogEntry>, String> {
+    let app_state = state
+        .lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    Ok(app_state.event_log.iter().cloned().collect())
+}
+
+// 睡眠セッションの記録を開始
+#[tauri::command]
+pub fn start_sleep_session(state: tauri::State<AppStateMutex>) -> Result<(), String> {
+    let mut app_state = state
+        .lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    start_session(&mut app_state)
+}
+
+// 睡眠セッションの記録を終了し、集計を取得
+#[tauri::command]
+pub async fn end_sleep_session(
+    state: tauri::State<'_, AppStateMutex>,
+) -> Result<Option<SleepReport>, String> {
+    // ファイルへの保存はロックを解放してから行う
+    let snapshot = {
+        let mut app_state = state
+            .lock()
+            .map_err(|e| format!("Failed to lock state: {}", e))?;
+        finish_session(&mut app_state)
+    };
+    match snapshot {
+        Some(snapshot) => save_finished_session(state.inner(), snapshot).await.map(Some),
+        None => Ok(None),
+    }
+}
+
+// 保存された睡眠セッションの集計を取得
+#[tauri::command]
+pub fn get_sleep_reports() -> Result<Vec<SleepReport>, String> {
+    load_reports()
+}
+
+// 睡眠セッションをCSVまたはJSONとして書き出し
+#[tauri::command]
+pub fn export_sleep_session(id: String, format: String, path: String) -> Result<(), String> {
+    export_session(&id, &format, &path)
+}
+
 // 現在のバージョンを取得
 #[tauri::command]
 pub fn get_current_version() -> String {
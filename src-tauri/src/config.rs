@@ -2,10 +2,16 @@ use crate::types::AlarmSettings;
 use std::fs;
 use std::path::PathBuf;
 
-// 設定ファイル管理
-pub fn get_config_path() -> PathBuf {
+// アプリのデータ保存先ディレクトリ
+pub fn get_config_dir() -> PathBuf {
     let mut path = dirs::config_dir().unwrap_or_else(|| PathBuf::from("."));
     path.push("vrchat-anywhere-alarm");
+    path
+}
+
+// 設定ファイル管理
+pub fn get_config_path() -> PathBuf {
+    let mut path = get_config_dir();
     path.push("settings.json");
     path
 }
@@ -0,0 +1,195 @@
+use crate::chatbox::{notify_chatbox, ChatboxEvent};
+use crate::osc::send_osc_to_vrchat;
+use crate::timer::handle_timer_event;
+use crate::types::{
+    AlarmSource, AppState, AppStateMutex, EventKind, TimerEvent, TimerManagerMutex,
+};
+use chrono::{DateTime, Utc};
+use rosc::OscType;
+use serde::{Deserialize, Serialize};
+use tokio::time::{sleep, Duration};
+
+// カウントダウンの最大分数（ラジアルメニューの最大値に対応）
+pub const COUNTDOWN_MAX_MINUTES: u32 = 120;
+
+/// カウントダウンタイマーの設定
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CountdownSettings {
+    pub presets: Vec<u32>, // CountdownPresetの番号（1から）に対応する分数
+}
+
+impl Default for CountdownSettings {
+    fn default() -> Self {
+        Self {
+            presets: vec![5, 10, 15, 25, 30, 60],
+        }
+    }
+}
+
+impl CountdownSettings {
+    // 各プリセットを有効範囲に丸め込み
+    pub fn clamped(self) -> Self {
+        Self {
+            presets: self
+                .presets
+                .into_iter()
+                .map(|minutes| minutes.clamp(1, COUNTDOWN_MAX_MINUTES))
+                .collect(),
+        }
+    }
+
+    // プリセット番号（1から）に対応する分数を取得
+    pub fn preset(&self, slot: i32) -> Option<u32> {
+        let index = usize::try_from(slot).ok()?.checked_sub(1)?;
+        self.presets.get(index).copied()
+    }
+}
+
+// カウントダウンを開始し、終了時刻を返す（既存のカウントダウン・仮眠は置き換える）
+pub async fn start_countdown(
+    state: AppStateMutex,
+    timer_manager: TimerManagerMutex,
+    minutes: u32,
+    source: AlarmSource,
+) -> Result<DateTime<Utc>, String> {
+    if minutes == 0 || minutes > COUNTDOWN_MAX_MINUTES {
+        return Err(format!(
+            "Countdown must be between 1 and {} minutes",
+            COUNTDOWN_MAX_MINUTES
+        ));
+    }
+
+    let ends_at = Utc::now() + chrono::Duration::minutes(minutes as i64);
+    {
+        let mut app_state = state
+            .lock()
+            .map_err(|e| format!("Failed to lock state: {}", e))?;
+        // 鳴動中・スヌーズ中のカウントダウン・仮眠のタイマーは置き換えない
+        if countdown_alarm_active(&app_state) {
+            return Err(format!(
+                "The {:?} alarm is still ringing or snoozing. Stop it first",
+                app_state.alarm_source
+            ));
+        }
+        app_state.countdown_ends_at = Some(ends_at);
+        app_state.countdown_total_minutes = minutes;
+        app_state.countdown_source = source;
+        app_state.log_event(
+            EventKind::CountdownStarted,
+            format!("{:?}: {} min", source, minutes),
+        );
+    }
+    println!("Countdown started ({:?}): {} minutes", source, minutes);
+
+    let state_clone = state.clone();
+    let timer_manager_clone = timer_manager.clone();
+    let countdown_handle = tokio::spawn(async move {
+        sleep(Duration::from_secs(minutes as u64 * 60)).await;
+        fire_countdown(state_clone, timer_manager_clone).await;
+    });
+    timer_manager
+        .lock()
+        .map_err(|e| format!("Failed to lock timer manager: {}", e))?
+        .set_countdown_timer(countdown_handle);
+
+    send_countdown_active(&state, true).await;
+    Ok(ends_at)
+}
+
+// 動作中のカウントダウンをキャンセル（鳴動中・スヌーズ中の場合はそのアラームを停止）
+pub async fn cancel_countdown(state: AppStateMutex, timer_manager: TimerManagerMutex) {
+    let alarm_active = state
+        .lock()
+        .map(|app_state| countdown_alarm_active(&app_state))
+        .unwrap_or(false);
+    if alarm_active {
+        handle_timer_event(state.clone(), timer_manager.clone(), TimerEvent::Stop).await;
+    }
+    if let Ok(mut timer_mgr) = timer_manager.lock() {
+        timer_mgr.cancel_countdown_timer();
+    }
+    let was_active = match state.lock() {
+        Ok(mut app_state) => {
+            let was_active = app_state.countdown_ends_at.take().is_some();
+            if was_active {
+                app_state.log_event(EventKind::CountdownCancelled, "");
+            }
+            was_active
+        }
+        Err(e) => {
+            eprintln!("Failed to lock state: {}", e);
+            return;
+        }
+    };
+    if was_active {
+        send_countdown_active(&state, false).await;
+    }
+}
+
+// カウントダウン・仮眠のアラームが鳴動中またはスヌーズ中かどうか
+fn countdown_alarm_active(state: &AppState) -> bool {
+    matches!(
+        state.alarm_source,
+        AlarmSource::Countdown | AlarmSource::Nap
+    ) && (state.is_ringing || state.snooze_until.is_some())
+}
+
+// カウントダウン終了時にアラームの鳴動・スヌーズ・停止の処理に引き渡す
+async fn fire_countdown(state: AppStateMutex, timer_manager: TimerManagerMutex) {
+    let (busy, minutes) = match state.lock() {
+        Ok(mut app_state) => {
+            let minutes = app_state.countdown_total_minutes;
+            let source = app_state.countdown_source;
+            // 鳴動中だけでなくスヌーズ中も、そのアラームの状態を上書きしないよう鳴らさない
+            if app_state.is_ringing || app_state.snooze_until.is_some() {
+                app_state.countdown_ends_at = None;
+                app_state.log_event(
+                    EventKind::AlarmSuppressed,
+                    format!("{:?}: {} min, another alarm is active", source, minutes),
+                );
+                (true, minutes)
+            } else {
+                app_state.alarm_source = source;
+                app_state.snooze_count = 0;
+                app_state.log_event(
+                    EventKind::AlarmFired,
+                    format!("{:?}: {} min", source, minutes),
+                );
+                (false, minutes)
+            }
+        }
+        Err(e) => {
+            eprintln!("Failed to lock state: {}", e);
+            return;
+        }
+    };
+    send_countdown_active(&state, false).await;
+
+    // 他のアラームが鳴動中・スヌーズ中の場合は重ねて鳴らさず、終了したことだけを知らせる
+    if busy {
+        println!("Countdown finished while another alarm is ringing or snoozing. Skipping.");
+        notify_chatbox(
+            &state,
+            ChatboxEvent::TimerMissed,
+            &[("minutes", minutes.to_string())],
+        )
+        .await;
+        return;
+    }
+    println!("Countdown finished!");
+    handle_timer_event(state, timer_manager, TimerEvent::AlarmFire).await;
+}
+
+// カウントダウンの動作状態をVRChatに送信
+async fn send_countdown_active(state: &AppStateMutex, active: bool) {
+    if let Err(e) = send_osc_to_vrchat(
+        "/avatar/parameters/CountdownActive",
+        vec![OscType::Bool(active)],
+        state,
+    )
+    .await
+    {
+        eprintln!("Failed to send countdown state: {}", e);
+    }
+}
@@ -0,0 +1,340 @@
+use chrono::{
+    DateTime, Datelike, Days, FixedOffset, Local, NaiveDate, NaiveDateTime, TimeZone, Utc,
+};
+use serde::{Deserialize, Serialize};
+
+// 月と曜日の名前（先頭が最小値に対応）
+const MONTH_NAMES: [&str; 12] = [
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+const WEEKDAY_NAMES: [&str; 7] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+// ローカルの日付に対して前後に探すcron式のタイムゾーンでの日数（時差は最大26時間）
+const CRON_DATE_MARGIN_DAYS: u64 = 2;
+
+/// cron式によるアラームの設定
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CronAlarmSettings {
+    pub expression: String,                   // cron式（分 時 日 月 曜日）
+    pub timezone_offset_minutes: Option<i32>, // 評価するタイムゾーン（UTCからの分数、未設定はシステム設定）
+}
+
+impl Default for CronAlarmSettings {
+    fn default() -> Self {
+        Self {
+            expression: "0 7 * * 1-5".to_string(),
+            timezone_offset_minutes: None,
+        }
+    }
+}
+
+impl CronAlarmSettings {
+    // 各値を有効範囲に丸め込み
+    pub fn clamped(self) -> Self {
+        Self {
+            expression: self.expression.trim().to_string(),
+            timezone_offset_minutes: self
+                .timezone_offset_minutes
+                .map(|offset| offset.clamp(-720, 840)),
+        }
+    }
+
+    // 設定値を検証
+    pub fn validate(&self) -> Result<(), String> {
+        CronSchedule::parse(&self.expression).map(|_| ())
+    }
+
+    // cron式を解析し、タイムゾーンと合わせたスケジュールを作成
+    pub fn schedule(&self) -> Result<CronSchedule, String> {
+        let mut schedule = CronSchedule::parse(&self.expression)?;
+        schedule.offset = self
+            .timezone_offset_minutes
+            .and_then(|minutes| FixedOffset::east_opt(minutes * 60));
+        Ok(schedule)
+    }
+}
+
+/// 解析したcron式（各フィールドは値ごとのビットで表す）
+#[derive(Debug, Clone, PartialEq)]
+pub struct CronSchedule {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    days_restricted: bool,       // 日が「*」以外で指定されているかどうか
+    weekdays_restricted: bool,   // 曜日が「*」以外で指定されているかどうか
+    offset: Option<FixedOffset>, // 評価するタイムゾーン（Noneはシステム設定）
+}
+
+impl CronSchedule {
+    // 5つのフィールド（分 時 日 月 曜日）からなるcron式を解析
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(format!(
+                "Cron expression must have 5 fields (minute hour day month weekday), got {}",
+                fields.len()
+            ));
+        };
+
+        // 曜日の7は日曜日として扱う
+        let weekdays = parse_field(weekday, "weekday", 0, 7, &WEEKDAY_NAMES)?;
+        Ok(Self {
+            minutes: parse_field(minute, "minute", 0, 59, &[])?,
+            hours: parse_field(hour, "hour", 0, 23, &[])?,
+            days: parse_field(day, "day", 1, 31, &[])?,
+            months: parse_field(month, "month", 1, 12, &MONTH_NAMES)?,
+            weekdays: (weekdays | (weekdays >> 7)) & 0x7f,
+            days_restricted: !day.starts_with('*'),
+            weekdays_restricted: !weekday.starts_with('*'),
+            offset: None,
+        })
+    }
+
+    // 指定した日がcron式に一致するかどうか
+    // 日と曜日の両方が指定されている場合は、どちらかに一致すればよい（一般的なcronと同じ）
+    fn date_matches(&self, date: NaiveDate) -> bool {
+        let day = has_bit(self.days, date.day());
+        let weekday = has_bit(self.weekdays, date.weekday().num_days_from_sunday());
+        let day_matches = match (self.days_restricted, self.weekdays_restricted) {
+            (true, true) => day || weekday,
+            _ => day && weekday,
+        };
+        has_bit(self.months, date.month()) && day_matches
+    }
+
+    // 指定した日（ローカルの日付）のうち、指定した時刻より後で最初に一致する時刻を取得
+    // cron式のタイムゾーンとの時差で日付がずれるため、前後の日も含めて一致する時刻を探す
+    pub fn first_on(&self, date: NaiveDate, after: DateTime<Local>) -> Option<DateTime<Local>> {
+        let first_cron_date = date.checked_sub_days(Days::new(CRON_DATE_MARGIN_DAYS))?;
+        first_cron_date
+            .iter_days()
+            .take(CRON_DATE_MARGIN_DAYS as usize * 2 + 1)
+            .filter(|cron_date| self.date_matches(*cron_date))
+            .flat_map(|cron_date| self.times_on(cron_date))
+            .find(|at| at.date_naive() >= date && *at > after)
+            .filter(|at| at.date_naive() == date)
+    }
+
+    // first_onと同じだが、1回だけスキップした時刻の場合はその日の次の時刻を取得
+    pub fn first_unskipped_on(
+        &self,
+        date: NaiveDate,
+        after: DateTime<Local>,
+        skip_at: Option<DateTime<Utc>>,
+    ) -> Option<DateTime<Local>> {
+        let at = self.first_on(date, after)?;
+        match skip_at == Some(at.with_timezone(&Utc)) {
+            true => self.first_on(date, at),
+            false => Some(at),
+        }
+    }
+
+    // 指定した日（cron式のタイムゾーンでの日付）に一致する時刻を順に列挙
+    fn times_on(&self, date: NaiveDate) -> impl Iterator<Item = DateTime<Local>> + '_ {
+        (0..24)
+            .filter(|hour| has_bit(self.hours, *hour))
+            .flat_map(move |hour| {
+                (0..60)
+                    .filter(|minute| has_bit(self.minutes, *minute))
+                    .filter_map(move |minute| date.and_hms_opt(hour, minute, 0))
+            })
+            .filter_map(|naive| self.to_local(naive))
+    }
+
+    // cron式のタイムゾーンでの日時をローカル時刻に変換
+    fn to_local(&self, naive: NaiveDateTime) -> Option<DateTime<Local>> {
+        match self.offset {
+            Some(offset) => offset
+                .from_local_datetime(&naive)
+                .single()
+                .map(|at| at.with_timezone(&Local)),
+            None => Local.from_local_datetime(&naive).earliest(),
+        }
+    }
+}
+
+fn has_bit(mask: u64, value: u32) -> bool {
+    mask & (1 << value) != 0
+}
+
+// フィールド（例: *、*/15、9-17/2、1,3,5、MON-FRI）を解析し、一致する値のビットを返す
+fn parse_field(field: &str, name: &str, min: u32, max: u32, names: &[&str]) -> Result<u64, String> {
+    let mut mask = 0;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step = step
+                    .parse::<u32>()
+                    .ok()
+                    .filter(|step| *step > 0)
+                    .ok_or_else(|| {
+                        format!(
+                            "Invalid {} field '{}': step must be a positive number",
+                            name, field
+                        )
+                    })?;
+                (range, step)
+            }
+            None => (part, 1),
+        };
+        let (start, end) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((start, end)) => (
+                    parse_value(start, name, field, min, max, names)?,
+                    parse_value(end, name, field, min, max, names)?,
+                ),
+                // 「5/15」のように開始だけを指定した場合は最大値まで
+                None if step > 1 => (parse_value(range, name, field, min, max, names)?, max),
+                None => {
+                    let value = parse_value(range, name, field, min, max, names)?;
+                    (value, value)
+                }
+            },
+        };
+        if start > end {
+            return Err(format!(
+                "Invalid {} field '{}': range {}-{} is reversed",
+                name, field, start, end
+            ));
+        }
+        for value in (start..=end).step_by(step as usize) {
+            mask |= 1 << value;
+        }
+    }
+    Ok(mask)
+}
+
+// 数値または名前（JAN、MONなど）を解析
+fn parse_value(
+    value: &str,
+    name: &str,
+    field: &str,
+    min: u32,
+    max: u32,
+    names: &[&str],
+) -> Result<u32, String> {
+    if let Some(index) = names
+        .iter()
+        .position(|candidate| candidate.eq_ignore_ascii_case(value))
+    {
+        return Ok(min + index as u32);
+    }
+    match value.parse::<u32>() {
+        Ok(number) if (min..=max).contains(&number) => Ok(number),
+        Ok(number) => Err(format!(
+            "Invalid {} field '{}': {} is out of range ({}-{})",
+            name, field, number, min, max
+        )),
+        Err(_) => Err(format!(
+            "Invalid {} field '{}': '{}' is not a number",
+            name, field, value
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schedule::next_alarm_time;
+
+    // UTC+9で評価するスケジュールを作成
+    fn tokyo_schedule(expression: &str) -> CronSchedule {
+        CronAlarmSettings {
+            expression: expression.to_string(),
+            timezone_offset_minutes: Some(540),
+        }
+        .schedule()
+        .unwrap()
+    }
+
+    fn tokyo(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Local> {
+        FixedOffset::east_opt(9 * 3600)
+            .unwrap()
+            .with_ymd_and_hms(year, month, day, hour, minute, 0)
+            .unwrap()
+            .with_timezone(&Local)
+    }
+
+    #[test]
+    fn every_two_hours_on_weekdays() {
+        let schedule = tokyo_schedule("0 9-17/2 * * MON-FRI");
+        // 2026-10-19は月曜日
+        for (after, expected) in [
+            (tokyo(2026, 10, 19, 9, 30), tokyo(2026, 10, 19, 11, 0)),
+            (tokyo(2026, 10, 19, 17, 0), tokyo(2026, 10, 20, 9, 0)),
+            (tokyo(2026, 10, 18, 0, 0), tokyo(2026, 10, 19, 9, 0)),
+        ] {
+            let next = next_alarm_time(after, &[], |date| schedule.first_on(date, after));
+            assert_eq!(next, Some(expected));
+        }
+    }
+
+    #[test]
+    fn skips_only_the_skipped_time() {
+        let schedule = tokyo_schedule("0 9-17/2 * * MON-FRI");
+        let after = tokyo(2026, 10, 19, 8, 0);
+        let skip_at = Some(tokyo(2026, 10, 19, 9, 0).with_timezone(&Utc));
+        let next = next_alarm_time(after, &[], |date| {
+            schedule.first_unskipped_on(date, after, skip_at)
+        });
+        assert_eq!(next, Some(tokyo(2026, 10, 19, 11, 0)));
+        // 最後の時刻をスキップした場合は翌日の最初の時刻
+        let after = tokyo(2026, 10, 19, 16, 0);
+        let skip_at = Some(tokyo(2026, 10, 19, 17, 0).with_timezone(&Utc));
+        let next = next_alarm_time(after, &[], |date| {
+            schedule.first_unskipped_on(date, after, skip_at)
+        });
+        assert_eq!(next, Some(tokyo(2026, 10, 20, 9, 0)));
+    }
+
+    #[test]
+    fn searches_across_time_zones() {
+        // UTC-12で評価する式（東京とは21時間の時差）
+        let schedule = CronAlarmSettings {
+            expression: "0 9-17/2 * * *".to_string(),
+            timezone_offset_minutes: Some(-720),
+        }
+        .schedule()
+        .unwrap();
+        // UTC-12の前日9:00は東京の6:00、前日17:00は東京の14:00
+        for (after, expected) in [
+            (tokyo(2026, 10, 19, 3, 0), tokyo(2026, 10, 19, 6, 0)),
+            (tokyo(2026, 10, 19, 13, 0), tokyo(2026, 10, 19, 14, 0)),
+        ] {
+            let next = next_alarm_time(after, &[], |date| schedule.first_on(date, after));
+            assert_eq!(next, Some(expected));
+            assert_eq!(
+                schedule.first_on(expected.date_naive(), after),
+                Some(expected)
+            );
+        }
+    }
+
+    #[test]
+    fn day_and_weekday_match_either() {
+        // 13日または金曜日
+        let schedule = tokyo_schedule("30 7 13 * 5");
+        for (day, matches) in [(12, false), (13, true), (20, true), (21, false)] {
+            let date = NaiveDate::from_ymd_opt(2026, 11, day).unwrap();
+            assert_eq!(schedule.date_matches(date), matches, "2026-11-{}", day);
+        }
+    }
+
+    #[test]
+    fn reports_invalid_fields() {
+        assert_eq!(
+            CronSchedule::parse("0 7 * *").unwrap_err(),
+            "Cron expression must have 5 fields (minute hour day month weekday), got 4"
+        );
+        assert_eq!(
+            CronSchedule::parse("0 24 * * *").unwrap_err(),
+            "Invalid hour field '24': 24 is out of range (0-23)"
+        );
+        assert!(CronSchedule::parse("*/0 * * * *").is_err());
+        assert!(CronSchedule::parse("0 17-9 * * *").is_err());
+        assert!(CronSchedule::parse("0 7 * * FUNDAY").is_err());
+    }
+}
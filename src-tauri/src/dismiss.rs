@@ -0,0 +1,315 @@
+use crate::chatbox::{notify_chatbox, ChatboxEvent};
+use crate::osc::send_osc_bundle_to_vrchat;
+use crate::types::{AppState, AppStateMutex};
+use chrono::{DateTime, Utc};
+use rosc::{OscMessage, OscType};
+use serde::{Deserialize, Serialize};
+
+/// 停止チャレンジの難易度
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChallengeDifficulty {
+    #[default]
+    Easy, // 1桁の足し算
+    Normal, // 2桁の足し算・引き算
+    Hard,   // 掛け算
+}
+
+/// 停止チャレンジの設定
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChallengeSettings {
+    pub enabled: bool,                   // 停止に計算問題の正解を必要とするかどうか
+    pub difficulty: ChallengeDifficulty, // 問題の難易度
+}
+
+/// 動きによる停止のモード
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MovementDismissMode {
+    #[default]
+    Off,
+    RequireToStop, // 条件を満たすまでStopPressedを受け付けない
+    AutoStop,      // 条件を満たしたら自動的に停止する
+}
+
+/// 動きによる停止の設定
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MovementDismissSettings {
+    pub mode: MovementDismissMode, // 停止のモード
+    pub velocity_threshold: f32,   // VelocityMagnitudeのしきい値
+    pub upright_threshold: f32,    // Uprightのしきい値
+    pub require_grounded: bool,    // 接地していることを条件にするかどうか
+    pub required_seconds: u32,     // 条件を満たし続ける必要がある秒数
+}
+
+impl Default for MovementDismissSettings {
+    fn default() -> Self {
+        Self {
+            mode: MovementDismissMode::Off,
+            velocity_threshold: 0.5,
+            upright_threshold: 0.9,
+            require_grounded: true,
+            required_seconds: 10,
+        }
+    }
+}
+
+impl MovementDismissSettings {
+    // 各値を有効範囲に丸め込み
+    pub fn clamped(self) -> Self {
+        Self {
+            velocity_threshold: self.velocity_threshold.clamp(0.0, 10.0),
+            upright_threshold: self.upright_threshold.clamp(0.0, 1.0),
+            required_seconds: self.required_seconds.clamp(1, 600),
+            ..self
+        }
+    }
+}
+
+/// 計算問題の演算子
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChallengeOperator {
+    Add,
+    Subtract,
+    Multiply,
+}
+
+impl ChallengeOperator {
+    // VRChatに送信する演算子の番号
+    fn parameter_value(self) -> i32 {
+        match self {
+            ChallengeOperator::Add => 0,
+            ChallengeOperator::Subtract => 1,
+            ChallengeOperator::Multiply => 2,
+        }
+    }
+
+    fn symbol(self) -> &'static str {
+        match self {
+            ChallengeOperator::Add => "+",
+            ChallengeOperator::Subtract => "-",
+            ChallengeOperator::Multiply => "×",
+        }
+    }
+}
+
+/// 鳴動中に出題する計算問題
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Challenge {
+    pub left: i32,
+    pub right: i32,
+    pub operator: ChallengeOperator,
+    pub solved: bool,
+}
+
+impl Challenge {
+    /// 難易度に応じた問題を作成（答えはVRChatのIntパラメータに収まる0-255の範囲）
+    pub fn generate(difficulty: ChallengeDifficulty, random: u64) -> Self {
+        let pick =
+            |shift: u32, low: u64, high: u64| (low + (random >> shift) % (high - low + 1)) as i32;
+        let (left, right, operator) = match difficulty {
+            ChallengeDifficulty::Easy => (pick(0, 1, 9), pick(16, 1, 9), ChallengeOperator::Add),
+            ChallengeDifficulty::Normal => {
+                let (a, b) = (pick(0, 10, 99), pick(16, 10, 99));
+                if random >> 32 & 1 == 0 {
+                    (a, b, ChallengeOperator::Add)
+                } else {
+                    (a.max(b), a.min(b), ChallengeOperator::Subtract)
+                }
+            }
+            ChallengeDifficulty::Hard => {
+                (pick(0, 3, 15), pick(16, 3, 15), ChallengeOperator::Multiply)
+            }
+        };
+        Self {
+            left,
+            right,
+            operator,
+            solved: false,
+        }
+    }
+
+    pub fn answer(&self) -> i32 {
+        match self.operator {
+            ChallengeOperator::Add => self.left + self.right,
+            ChallengeOperator::Subtract => self.left - self.right,
+            ChallengeOperator::Multiply => self.left * self.right,
+        }
+    }
+
+    // チャットボックス表示用の問題文
+    pub fn text(&self) -> String {
+        format!(
+            "{} {} {} = ?",
+            self.left,
+            self.operator.symbol(),
+            self.right
+        )
+    }
+}
+
+// 停止の条件を満たしているかどうか
+pub fn stop_allowed(state: &AppState) -> bool {
+    let challenge_solved = !matches!(state.challenge, Some(ref challenge) if !challenge.solved);
+    let movement_done = match state.movement_dismiss {
+        Some(ref settings) if settings.mode != MovementDismissMode::Off => state.movement_satisfied,
+        _ => true,
+    };
+    challenge_solved && movement_done
+}
+
+// 受信した動きから停止条件の達成状況を更新し、今回達成した場合はtrueを返す
+pub fn update_movement_progress(state: &mut AppState, now: DateTime<Utc>) -> bool {
+    let settings = match state.movement_dismiss {
+        Some(ref settings) if state.is_ringing && !state.movement_satisfied => settings.clone(),
+        _ => return false,
+    };
+
+    // 直立して一定以上の速度で動いている間だけ経過時間を数える
+    let motion = &state.motion;
+    let moving = motion.upright >= settings.upright_threshold
+        && motion.velocity_magnitude >= settings.velocity_threshold
+        && (motion.grounded || !settings.require_grounded);
+    if !moving {
+        state.movement_active_since = None;
+        return false;
+    }
+
+    let since = *state.movement_active_since.get_or_insert(now);
+    if now.signed_duration_since(since).num_seconds() < settings.required_seconds as i64 {
+        return false;
+    }
+    state.movement_satisfied = true;
+    true
+}
+
+// 動きによる停止条件の達成をVRChatに送信
+pub async fn send_movement_ready(state: &AppStateMutex, ready: bool) {
+    let messages = vec![OscMessage {
+        addr: "/avatar/parameters/MovementDismissReady".to_string(),
+        args: vec![OscType::Bool(ready)],
+    }];
+    if let Err(e) = send_osc_bundle_to_vrchat(messages, state).await {
+        eprintln!("Failed to send movement dismiss state: {}", e);
+    }
+}
+
+// 計算問題をVRChatとチャットボックスに送信
+pub async fn send_challenge(state: &AppStateMutex, challenge: &Challenge) {
+    let messages = vec![
+        OscMessage {
+            addr: "/avatar/parameters/ChallengeLeft".to_string(),
+            args: vec![OscType::Int(challenge.left)],
+        },
+        OscMessage {
+            addr: "/avatar/parameters/ChallengeRight".to_string(),
+            args: vec![OscType::Int(challenge.right)],
+        },
+        OscMessage {
+            addr: "/avatar/parameters/ChallengeOperator".to_string(),
+            args: vec![OscType::Int(challenge.operator.parameter_value())],
+        },
+        OscMessage {
+            addr: "/avatar/parameters/ChallengeActive".to_string(),
+            args: vec![OscType::Bool(!challenge.solved)],
+        },
+    ];
+    if let Err(e) = send_osc_bundle_to_vrchat(messages, state).await {
+        eprintln!("Failed to send challenge: {}", e);
+    }
+    if !challenge.solved {
+        notify_chatbox(
+            state,
+            ChatboxEvent::Challenge,
+            &[("problem", challenge.text())],
+        )
+        .await;
+    }
+}
+
+// 鳴動終了時に出題中の計算問題と動きによる停止条件を取り下げる
+pub async fn clear_dismissal(state: &AppStateMutex) {
+    let (had_challenge, had_movement) = match state.lock() {
+        Ok(mut app_state) => {
+            app_state.movement_active_since = None;
+            app_state.movement_satisfied = false;
+            (
+                app_state.challenge.take().is_some(),
+                app_state.movement_dismiss.take().is_some(),
+            )
+        }
+        Err(e) => {
+            eprintln!("Failed to lock state: {}", e);
+            return;
+        }
+    };
+    if had_challenge {
+        let messages = vec![OscMessage {
+            addr: "/avatar/parameters/ChallengeActive".to_string(),
+            args: vec![OscType::Bool(false)],
+        }];
+        if let Err(e) = send_osc_bundle_to_vrchat(messages, state).await {
+            eprintln!("Failed to clear challenge: {}", e);
+        }
+    }
+    if had_movement {
+        send_movement_ready(state, false).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generated_answers_fit_in_int_parameter() {
+        let difficulties = [
+            ChallengeDifficulty::Easy,
+            ChallengeDifficulty::Normal,
+            ChallengeDifficulty::Hard,
+        ];
+        for difficulty in difficulties {
+            for random in [0, 1, u64::MAX, 0x1234_5678_9abc_def0, 1 << 32] {
+                let answer = Challenge::generate(difficulty, random).answer();
+                assert!((0..=255).contains(&answer), "{:?} {}", difficulty, answer);
+            }
+        }
+    }
+
+    #[test]
+    fn stop_requires_solved_challenge() {
+        let challenge = Challenge {
+            left: 12,
+            right: 5,
+            operator: ChallengeOperator::Subtract,
+            solved: false,
+        };
+        assert_eq!(challenge.answer(), 7);
+        assert_ne!(challenge.answer(), 17);
+
+        let mut state = AppState {
+            challenge: Some(challenge),
+            ..AppState::default()
+        };
+        assert!(!stop_allowed(&state));
+        state.challenge.as_mut().unwrap().solved = true;
+        assert!(stop_allowed(&state));
+    }
+
+    #[test]
+    fn stop_requires_movement_when_enabled() {
+        let mut state = AppState {
+            movement_dismiss: Some(MovementDismissSettings {
+                mode: MovementDismissMode::RequireToStop,
+                ..MovementDismissSettings::default()
+            }),
+            ..AppState::default()
+        };
+        assert!(!stop_allowed(&state));
+        state.movement_satisfied = true;
+        assert!(stop_allowed(&state));
+    }
+}
@@ -0,0 +1,97 @@
+use crate::types::RampCurve;
+use serde::{Deserialize, Serialize};
+
+/// アラームの強さの段階的な上昇設定
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EscalationSettings {
+    pub enabled: bool,        // 段階的な上昇を有効にするかどうか
+    pub base_intensity: f32,  // 最初の発火時の強さ
+    pub per_snooze_step: f32, // スヌーズ1回ごとの上昇量
+    pub per_minute_step: f32, // 鳴動1分ごとの上昇量
+    pub max_intensity: f32,   // 強さの上限
+    pub curve: RampCurve,     // 基準値から上限までの変化曲線
+}
+
+impl Default for EscalationSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            base_intensity: 0.3,
+            per_snooze_step: 0.15,
+            per_minute_step: 0.05,
+            max_intensity: 1.0,
+            curve: RampCurve::Linear,
+        }
+    }
+}
+
+impl EscalationSettings {
+    // 各値を有効範囲に丸め込み
+    pub fn clamped(self) -> Self {
+        let base_intensity = self.base_intensity.clamp(0.0, 1.0);
+        Self {
+            base_intensity,
+            per_snooze_step: self.per_snooze_step.clamp(0.0, 1.0),
+            per_minute_step: self.per_minute_step.clamp(0.0, 1.0),
+            max_intensity: self.max_intensity.clamp(base_intensity, 1.0),
+            ..self
+        }
+    }
+
+    // スヌーズ回数と鳴動経過時間からアラームの強さを計算
+    pub fn intensity(&self, snooze_count: u32, ringing_minutes: f32) -> f32 {
+        let range = self.max_intensity - self.base_intensity;
+        if range <= 0.0 {
+            return self.max_intensity;
+        }
+        let raw_step = self.per_snooze_step * snooze_count as f32
+            + self.per_minute_step * ringing_minutes.max(0.0);
+        self.base_intensity + range * self.curve.apply(raw_step / range)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn starts_at_base_intensity() {
+        let escalation = EscalationSettings::default().clamped();
+        assert_eq!(escalation.intensity(0, 0.0), 0.3);
+    }
+
+    #[test]
+    fn reaches_max_at_max_snoozes() {
+        let escalation = EscalationSettings::default().clamped();
+        // 既定の最大スヌーズ回数（5回）で上限に達する
+        assert_eq!(escalation.intensity(5, 0.0), 1.0);
+        assert_eq!(escalation.intensity(20, 60.0), 1.0);
+    }
+
+    #[test]
+    fn never_exceeds_configured_max() {
+        let escalation = EscalationSettings {
+            max_intensity: 0.8,
+            curve: RampCurve::Exponential,
+            ..EscalationSettings::default()
+        }
+        .clamped();
+        for snooze_count in 0..=10 {
+            let intensity = escalation.intensity(snooze_count, snooze_count as f32 * 9.0);
+            assert!((0.3..=0.8).contains(&intensity), "{}", intensity);
+        }
+        assert_eq!(escalation.intensity(10, 0.0), 0.8);
+    }
+
+    #[test]
+    fn clamps_max_below_base() {
+        let escalation = EscalationSettings {
+            base_intensity: 0.6,
+            max_intensity: 0.2,
+            ..EscalationSettings::default()
+        }
+        .clamped();
+        assert_eq!(escalation.intensity(3, 5.0), 0.6);
+    }
+}
@@ -0,0 +1,287 @@
+use crate::calendar::parse_calendar;
+use chrono::{Datelike, Local, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+
+// 複数日にわたる予定として読み込む最大日数
+const ICS_MAX_EVENT_DAYS: i64 = 366;
+// カレンダー作成時に日本の祝日を計算しておく年数（次のアラームの探索範囲を含む）
+const CACHED_HOLIDAY_YEARS: i32 = 3;
+
+/// 祝日のアラームの扱い
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HolidayAction {
+    /// 鳴らさない
+    #[default]
+    Skip,
+    /// 別の時刻に鳴らす
+    Shift,
+}
+
+/// 祝日カレンダーの設定
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HolidaySettings {
+    pub enabled: bool,               // 祝日を考慮するかどうか
+    pub japanese_holidays: bool,     // 日本の祝日を含めるかどうか
+    pub extra_dates: Vec<NaiveDate>, // 追加の休日（YYYY-MM-DD）
+    pub ics_paths: Vec<String>,      // 休日として読み込む.icsファイル
+    pub action: HolidayAction,       // 祝日のアラームの扱い
+    pub shift_hour: u32,             // 祝日に鳴らす時刻（時）
+    pub shift_minute: u32,           // 祝日に鳴らす時刻（分）
+}
+
+impl Default for HolidaySettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            japanese_holidays: true,
+            extra_dates: Vec::new(),
+            ics_paths: Vec::new(),
+            action: HolidayAction::Skip,
+            shift_hour: 9,
+            shift_minute: 0,
+        }
+    }
+}
+
+impl HolidaySettings {
+    // 各値を有効範囲に丸め込み
+    pub fn clamped(self) -> Self {
+        Self {
+            shift_hour: self.shift_hour.min(23),
+            shift_minute: self.shift_minute.min(59),
+            ..self
+        }
+    }
+}
+
+/// 祝日の判定に使用するカレンダー
+#[derive(Debug, Clone, Default)]
+pub struct HolidayCalendar {
+    japanese_holidays: bool,
+    dates: BTreeSet<NaiveDate>, // 追加の休日と.icsファイルから読み込んだ休日
+    japanese_by_year: BTreeMap<i32, BTreeSet<NaiveDate>>, // 年ごとに計算済みの日本の祝日
+}
+
+impl HolidayCalendar {
+    // 設定からカレンダーを作成（読み込めない.icsファイルは無視する）
+    pub fn load(settings: &HolidaySettings) -> Self {
+        // 日付ごとに計算し直さないよう、今年から探索範囲の年までの祝日を計算しておく
+        let this_year = Local::now().year();
+        let last_date = NaiveDate::from_ymd_opt(this_year + CACHED_HOLIDAY_YEARS - 1, 12, 31)
+            .unwrap_or(NaiveDate::MAX);
+
+        let mut dates: BTreeSet<NaiveDate> = settings.extra_dates.iter().copied().collect();
+        for path in &settings.ics_paths {
+            match fs::read_to_string(path) {
+                Ok(content) => dates.extend(calendar_dates(&content, last_date)),
+                Err(e) => eprintln!("Failed to read holiday calendar {}: {}", path, e),
+            }
+        }
+        let japanese_by_year = if settings.japanese_holidays {
+            (this_year..this_year + CACHED_HOLIDAY_YEARS)
+                .map(|year| (year, japanese_holidays(year)))
+                .collect()
+        } else {
+            BTreeMap::new()
+        };
+        Self {
+            japanese_holidays: settings.japanese_holidays,
+            dates,
+            japanese_by_year,
+        }
+    }
+
+    pub fn is_holiday(&self, date: NaiveDate) -> bool {
+        if self.dates.contains(&date) {
+            return true;
+        }
+        if !self.japanese_holidays {
+            return false;
+        }
+        match self.japanese_by_year.get(&date.year()) {
+            Some(holidays) => holidays.contains(&date),
+            // 計算していない年は都度計算する
+            None => japanese_holidays(date.year()).contains(&date),
+        }
+    }
+}
+
+// .icsファイルの予定の日付を指定した日まで取得（繰り返しを展開し、終日の予定は終了日を含まない）
+// 対応していない繰り返しなどで読み込めなかった予定も、祝日を失わないよう開始日だけは使う
+fn calendar_dates(content: &str, last_date: NaiveDate) -> Vec<NaiveDate> {
+    let parsed = parse_calendar(content);
+    let fallback_dates = parsed
+        .skipped
+        .iter()
+        .filter_map(|event| event.start)
+        .map(|start| start.date())
+        .filter(|date| *date <= last_date);
+    parsed
+        .alarms
+        .iter()
+        .flat_map(|event| {
+            let days = event
+                .end
+                .map(|end| {
+                    end.date()
+                        .signed_duration_since(event.start.date())
+                        .num_days()
+                })
+                .unwrap_or(1)
+                .clamp(1, ICS_MAX_EVENT_DAYS);
+            event
+                .occurrences()
+                .map(|start| start.date())
+                .take_while(move |start| *start <= last_date)
+                .flat_map(move |start| start.iter_days().take(days as usize))
+        })
+        .chain(fallback_dates)
+        .collect()
+}
+
+// 日本の国民の祝日を計算（2020年以降の祝日法に基づく、春分・秋分は2099年まで有効な近似式）
+pub fn japanese_holidays(year: i32) -> BTreeSet<NaiveDate> {
+    let date = |month, day| NaiveDate::from_ymd_opt(year, month, day);
+    let mut holidays: BTreeSet<NaiveDate> = [
+        date(1, 1),                            // 元日
+        nth_weekday(year, 1, Weekday::Mon, 2), // 成人の日
+        date(2, 11),                           // 建国記念の日
+        date(2, 23),                           // 天皇誕生日
+        date(3, vernal_equinox_day(year)),     // 春分の日
+        date(4, 29),                           // 昭和の日
+        date(5, 3),                            // 憲法記念日
+        date(5, 4),                            // みどりの日
+        date(5, 5),                            // こどもの日
+        nth_weekday(year, 9, Weekday::Mon, 3), // 敬老の日
+        date(9, autumnal_equinox_day(year)),   // 秋分の日
+        date(11, 3),                           // 文化の日
+        date(11, 23),                          // 勤労感謝の日
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+
+    // 東京オリンピック・パラリンピックに伴う特例
+    let (marine_day, mountain_day, sports_day) = match year {
+        2020 => (date(7, 23), date(8, 10), date(7, 24)),
+        2021 => (date(7, 22), date(8, 8), date(7, 23)),
+        _ => (
+            nth_weekday(year, 7, Weekday::Mon, 3),
+            date(8, 11),
+            nth_weekday(year, 10, Weekday::Mon, 2),
+        ),
+    };
+    holidays.extend([marine_day, mountain_day, sports_day].into_iter().flatten());
+
+    // 国民の休日（前後を祝日に挟まれた日）
+    let sandwiched: Vec<NaiveDate> = holidays
+        .iter()
+        .filter_map(|holiday| {
+            let next = holiday.succ_opt()?;
+            let after_next = next.succ_opt()?;
+            (!holidays.contains(&next) && holidays.contains(&after_next)).then_some(next)
+        })
+        .collect();
+    holidays.extend(sandwiched);
+
+    // 振替休日（日曜日の祝日の後の最初の平日）
+    let substitutes: Vec<NaiveDate> = holidays
+        .iter()
+        .filter(|holiday| holiday.weekday() == Weekday::Sun)
+        .filter_map(|holiday| {
+            holiday
+                .iter_days()
+                .skip(1)
+                .find(|day| !holidays.contains(day))
+        })
+        .collect();
+    holidays.extend(substitutes);
+
+    holidays
+}
+
+// 指定した月の第n週の曜日を取得
+fn nth_weekday(year: i32, month: u32, weekday: Weekday, n: u8) -> Option<NaiveDate> {
+    NaiveDate::from_weekday_of_month_opt(year, month, weekday, n)
+}
+
+// 春分の日（3月の日）
+fn vernal_equinox_day(year: i32) -> u32 {
+    equinox_day(20.8431, year)
+}
+
+// 秋分の日（9月の日）
+fn autumnal_equinox_day(year: i32) -> u32 {
+    equinox_day(23.2488, year)
+}
+
+fn equinox_day(base: f64, year: i32) -> u32 {
+    let years = (year - 1980) as f64;
+    (base + 0.242194 * years - (years / 4.0).floor()).floor() as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ymd(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn japanese_holidays_2024() {
+        let holidays = japanese_holidays(2024);
+        assert_eq!(holidays.len(), 21);
+        for date in [
+            ymd(2024, 1, 8),   // 成人の日
+            ymd(2024, 2, 12),  // 振替休日
+            ymd(2024, 3, 20),  // 春分の日
+            ymd(2024, 5, 6),   // 振替休日
+            ymd(2024, 7, 15),  // 海の日
+            ymd(2024, 9, 23),  // 振替休日
+            ymd(2024, 10, 14), // スポーツの日
+            ymd(2024, 11, 4),  // 振替休日
+        ] {
+            assert!(holidays.contains(&date), "{} should be a holiday", date);
+        }
+    }
+
+    #[test]
+    fn citizens_holiday_between_holidays() {
+        // 敬老の日（9/21）と秋分の日（9/23）に挟まれた日
+        assert!(japanese_holidays(2026).contains(&ymd(2026, 9, 22)));
+    }
+
+    #[test]
+    fn ics_all_day_events_exclude_end_date() {
+        let content = "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20240812\r\nDTEND;VALUE=DATE:20240815\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+        assert_eq!(
+            calendar_dates(content, ymd(2030, 12, 31)),
+            vec![ymd(2024, 8, 12), ymd(2024, 8, 13), ymd(2024, 8, 14)]
+        );
+    }
+
+    #[test]
+    fn ics_recurring_events_are_expanded() {
+        // 毎年の創立記念日（折り返した件名と繰り返し）
+        let content = "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nSUMMARY:創立\r\n 記念日\r\nDTSTART;VALUE=DATE:20240601\r\nRRULE:FREQ=YEARLY\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+        assert_eq!(
+            calendar_dates(content, ymd(2026, 12, 31)),
+            vec![ymd(2024, 6, 1), ymd(2025, 6, 1), ymd(2026, 6, 1)]
+        );
+    }
+
+    #[test]
+    fn ics_unsupported_rules_keep_the_start_date() {
+        // BYMONTHDAYには対応していないため、開始日だけを祝日として使う
+        let content = "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nSUMMARY:月例休業日\r\nDTSTART;VALUE=DATE:20240615\r\nRRULE:FREQ=MONTHLY;BYMONTHDAY=15\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+        assert_eq!(
+            calendar_dates(content, ymd(2026, 12, 31)),
+            vec![ymd(2024, 6, 15)]
+        );
+    }
+}
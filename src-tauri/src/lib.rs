@@ -14,17 +14,40 @@ use std::sync::{Arc, Mutex};
 use tokio::time::{sleep, Duration};
 
 // モジュール定義
+mod bedtime;
+mod calendar;
+mod chatbox;
+mod clock;
 mod commands;
 mod config;
+mod countdown;
+mod cron;
+mod dismiss;
+mod escalation;
+mod holiday;
+mod nap;
 mod osc;
+mod pomodoro;
+mod presence;
+mod quiet;
+mod schedule;
+mod skip;
+mod sleep;
+mod smart_wake;
+mod snooze;
+mod solar;
+mod stopwatch;
 mod timer;
 mod types;
 mod utils;
 
 // 必要なモジュールのインポート
+use calendar::run_calendar_sync;
+use clock::run_clock_sender;
 use commands::*;
 use config::load_settings;
 use osc::{send_osc_to_vrchat, OscServer};
+use sleep::run_sleep_sampler;
 use timer::calculate_and_set_next_alarm;
 use types::{AppState, TimerManager};
 use utils::{hour_to_vrc_float, minute_to_vrc_float};
@@ -117,12 +140,24 @@ pub fn run() {
                     app_state.max_snoozes = settings.max_snoozes;
                     app_state.ringing_duration_minutes = settings.ringing_duration_minutes;
                     app_state.snooze_duration_minutes = settings.snooze_duration_minutes;
+                    app_state.clock_enabled = settings.clock_enabled;
+                    app_state.clock_interval_seconds = settings.clock_interval_seconds;
+                    app_state.timezone_offset_minutes = settings.timezone_offset_minutes;
                 }
 
                 // 次のアラームを計算してタイマーをセット
                 calculate_and_set_next_alarm(startup_state, startup_timer_mgr).await;
             });
 
+            // 時計パラメータの送信を開始
+            tauri::async_runtime::spawn(run_clock_sender(state.clone()));
+
+            // 睡眠セッション中の動きのサンプリングを開始
+            tauri::async_runtime::spawn(run_sleep_sampler(state.clone()));
+
+            // .icsファイルの予定の読み込みと定期的な読み込み直しを開始
+            tauri::async_runtime::spawn(run_calendar_sync(state.clone(), timer_mgr.clone()));
+
             // ハートビート送信用の状態クローン
             let heartbeat_state = state.clone();
             // VRChatへのハートビート送信を開始
@@ -160,6 +195,71 @@ pub fn run() {
             get_alarm_settings,
             save_timer_settings,
             get_timer_settings,
+            save_countdown_settings,
+            get_next_fire_times,
+            save_clock_settings,
+            get_clock_settings,
+            save_sunrise_settings,
+            get_sunrise_settings,
+            save_escalation_settings,
+            get_escalation_settings,
+            save_snooze_strategy,
+            get_snooze_strategy,
+            save_chatbox_settings,
+            get_chatbox_settings,
+            save_challenge_settings,
+            get_challenge_settings,
+            save_movement_dismiss_settings,
+            get_movement_dismiss_settings,
+            save_smart_wake_settings,
+            get_smart_wake_settings,
+            save_presence_rules,
+            get_presence_rules,
+            save_quiet_hours,
+            get_quiet_hours,
+            start_countdown_timer,
+            cancel_countdown_timer,
+            save_countdown_timer_settings,
+            get_countdown_timer_settings,
+            get_stopwatch_laps,
+            start_pomodoro,
+            stop_pomodoro,
+            skip_pomodoro_phase,
+            get_pomodoro_status,
+            save_pomodoro_settings,
+            get_pomodoro_settings,
+            start_nap_alarm,
+            suggest_wake_times,
+            save_nap_settings,
+            get_nap_settings,
+            save_alarm_weekdays,
+            get_alarm_weekdays,
+            save_bedtime_settings,
+            get_bedtime_settings,
+            save_alarm_type,
+            get_alarm_type,
+            save_solar_alarm_settings,
+            get_solar_alarm_settings,
+            save_cron_alarm_settings,
+            get_cron_alarm_settings,
+            save_holiday_settings,
+            get_holiday_settings,
+            skip_next_alarm,
+            cancel_skip_next_alarm,
+            add_vacation,
+            remove_vacation,
+            get_skip_settings,
+            import_calendar,
+            reload_calendar_now,
+            get_imported_alarms,
+            save_calendar_import_settings,
+            get_calendar_import_settings,
+            export_alarm_calendar,
+            get_event_log,
+            start_sleep_session,
+            end_sleep_session,
+            get_sleep_reports,
+            export_sleep_session,
             get_current_version,
             check_for_updates
         ])
@@ -0,0 +1,94 @@
+use crate::countdown::{start_countdown, COUNTDOWN_MAX_MINUTES};
+use crate::types::{AlarmSource, AppStateMutex, TimerManagerMutex};
+use chrono::{DateTime, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+
+// 睡眠サイクル1回の長さ（分）
+const SLEEP_CYCLE_MINUTES: u32 = 90;
+// 提案する睡眠サイクル数の範囲
+const SUGGESTED_CYCLES: std::ops::RangeInclusive<u32> = 3..=6;
+
+/// 仮眠のプリセット
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NapPreset {
+    #[default]
+    PowerNap, // 深い眠りに入る前に起きる短い仮眠
+    FullCycle, // 睡眠サイクル1回分の仮眠
+}
+
+/// 仮眠の設定
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NapSettings {
+    pub power_nap_minutes: u32,    // パワーナップの分数
+    pub full_cycle_minutes: u32,   // 睡眠サイクル1回分の分数
+    pub default_preset: NapPreset, // NapStartで使用するプリセット
+    pub fall_asleep_minutes: u32,  // 寝付くまでの分数（起床時刻の提案に使用）
+}
+
+impl Default for NapSettings {
+    fn default() -> Self {
+        Self {
+            power_nap_minutes: 20,
+            full_cycle_minutes: SLEEP_CYCLE_MINUTES,
+            default_preset: NapPreset::PowerNap,
+            fall_asleep_minutes: 15,
+        }
+    }
+}
+
+impl NapSettings {
+    // 各値を有効範囲に丸め込み
+    pub fn clamped(self) -> Self {
+        Self {
+            power_nap_minutes: self.power_nap_minutes.clamp(1, COUNTDOWN_MAX_MINUTES),
+            full_cycle_minutes: self.full_cycle_minutes.clamp(1, COUNTDOWN_MAX_MINUTES),
+            fall_asleep_minutes: self.fall_asleep_minutes.min(60),
+            ..self
+        }
+    }
+
+    // プリセットの分数を取得
+    pub fn minutes(&self, preset: NapPreset) -> u32 {
+        match preset {
+            NapPreset::PowerNap => self.power_nap_minutes,
+            NapPreset::FullCycle => self.full_cycle_minutes,
+        }
+    }
+}
+
+/// 睡眠サイクルに合わせた起床時刻の提案
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WakeTimeSuggestion {
+    pub cycles: u32,        // 睡眠サイクル数
+    pub wake_time: String,  // 起床時刻（HH:MM）
+    pub sleep_minutes: u32, // 睡眠時間（寝付くまでの時間を除く）
+}
+
+// 仮眠を開始し、起床時刻を返す（プリセット未指定の場合は既定のプリセット）
+pub async fn start_nap(
+    state: AppStateMutex,
+    timer_manager: TimerManagerMutex,
+    settings: &NapSettings,
+    preset: Option<NapPreset>,
+) -> Result<DateTime<Utc>, String> {
+    let minutes = settings.minutes(preset.unwrap_or(settings.default_preset));
+    start_countdown(state, timer_manager, minutes, AlarmSource::Nap).await
+}
+
+// 就寝時刻から睡眠サイクルの区切りに合わせた起床時刻を提案
+pub fn suggest_wake_times(bedtime: NaiveTime, fall_asleep_minutes: u32) -> Vec<WakeTimeSuggestion> {
+    let asleep_at = bedtime + chrono::Duration::minutes(fall_asleep_minutes as i64);
+    SUGGESTED_CYCLES
+        .map(|cycles| {
+            let sleep_minutes = cycles * SLEEP_CYCLE_MINUTES;
+            let wake_time = asleep_at + chrono::Duration::minutes(sleep_minutes as i64);
+            WakeTimeSuggestion {
+                cycles,
+                wake_time: wake_time.format("%H:%M").to_string(),
+                sleep_minutes,
+            }
+        })
+        .collect()
+}
@@ -1,13 +1,105 @@
 use crate::config::{load_settings, save_settings};
-use crate::timer::{calculate_and_set_next_alarm, handle_timer_event};
-use crate::types::{AlarmSettings, AppStateMutex, TimerEvent, TimerManagerMutex};
-use crate::utils::{hour_to_vrc_float, minute_to_vrc_float, vrc_float_to_hour, vrc_float_to_minute};
+use crate::countdown::{cancel_countdown, start_countdown};
+use crate::dismiss::{
+    send_challenge, send_movement_ready, stop_allowed, update_movement_progress,
+    MovementDismissMode,
+};
+use crate::nap::start_nap;
+use crate::pomodoro::{apply_pomodoro_action, PomodoroAction};
+use crate::skip::{cancel_skip_next_alarm, pause_alarms, skip_next_alarm};
+use crate::sleep::start_session;
+use crate::stopwatch::{apply_stopwatch_action, StopwatchAction};
+use crate::timer::{calculate_and_set_next_alarm, handle_timer_event, next_fire_info};
+use crate::types::{
+    AlarmSettings, AlarmSource, AppState, AppStateMutex, TimerEvent, TimerManagerMutex,
+};
+use crate::utils::{
+    clamp_max_snoozes, clamp_ringing_duration_minutes, clamp_snooze_duration_minutes,
+    hour_to_vrc_float, minute_to_vrc_float, vrc_float_to_countdown_minutes, vrc_float_to_hour,
+    vrc_float_to_minute,
+};
 use chrono::Utc;
 use rosc::{OscMessage, OscPacket, OscType};
 use std::net::SocketAddr;
 use tauri::Emitter;
 use tokio::net::UdpSocket;
 
+/// VRChatから変更可能なタイマー設定
+#[derive(Debug, Clone, Copy)]
+enum TimerSetting {
+    MaxSnoozes,
+    RingingDuration,
+    SnoozeDuration,
+}
+
+impl TimerSetting {
+    /// 絶対値を設定するパラメータのアドレスから取得
+    fn from_address(addr: &str) -> Option<Self> {
+        match addr {
+            "/avatar/parameters/AlarmSetMaxSnoozes" => Some(Self::MaxSnoozes),
+            "/avatar/parameters/AlarmSetRingingDuration" => Some(Self::RingingDuration),
+            "/avatar/parameters/AlarmSetSnoozeDuration" => Some(Self::SnoozeDuration),
+            _ => None,
+        }
+    }
+
+    /// 増減ボタンのパラメータのアドレスから設定と増減量を取得
+    fn from_step_address(addr: &str) -> Option<(Self, i64)> {
+        match addr {
+            "/avatar/parameters/MaxSnoozesUp" => Some((Self::MaxSnoozes, 1)),
+            "/avatar/parameters/MaxSnoozesDown" => Some((Self::MaxSnoozes, -1)),
+            "/avatar/parameters/RingingDurationUp" => Some((Self::RingingDuration, 1)),
+            "/avatar/parameters/RingingDurationDown" => Some((Self::RingingDuration, -1)),
+            "/avatar/parameters/SnoozeDurationUp" => Some((Self::SnoozeDuration, 1)),
+            "/avatar/parameters/SnoozeDurationDown" => Some((Self::SnoozeDuration, -1)),
+            _ => None,
+        }
+    }
+
+    /// VRC側に値を同期する際のアドレス
+    fn address(self) -> &'static str {
+        match self {
+            Self::MaxSnoozes => "/avatar/parameters/AlarmSetMaxSnoozes",
+            Self::RingingDuration => "/avatar/parameters/AlarmSetRingingDuration",
+            Self::SnoozeDuration => "/avatar/parameters/AlarmSetSnoozeDuration",
+        }
+    }
+
+    /// save_timer_settingsと同じ範囲に丸め込み
+    fn clamp(self, value: i64) -> u32 {
+        let value = value.clamp(0, u32::MAX as i64) as u32;
+        match self {
+            Self::MaxSnoozes => clamp_max_snoozes(value),
+            Self::RingingDuration => clamp_ringing_duration_minutes(value),
+            Self::SnoozeDuration => clamp_snooze_duration_minutes(value),
+        }
+    }
+
+    fn current(self, state: &AppState) -> u32 {
+        match self {
+            Self::MaxSnoozes => state.max_snoozes,
+            Self::RingingDuration => state.ringing_duration_minutes,
+            Self::SnoozeDuration => state.snooze_duration_minutes,
+        }
+    }
+
+    fn apply_to_state(self, state: &mut AppState, value: u32) {
+        match self {
+            Self::MaxSnoozes => state.max_snoozes = value,
+            Self::RingingDuration => state.ringing_duration_minutes = value,
+            Self::SnoozeDuration => state.snooze_duration_minutes = value,
+        }
+    }
+
+    fn apply_to_settings(self, settings: &mut AlarmSettings, value: u32) {
+        match self {
+            Self::MaxSnoozes => settings.max_snoozes = value,
+            Self::RingingDuration => settings.ringing_duration_minutes = value,
+            Self::SnoozeDuration => settings.snooze_duration_minutes = value,
+        }
+    }
+}
+
 /// OSCサーバー構造体
 pub struct OscServer {
     state: AppStateMutex,
@@ -51,6 +143,33 @@ impl OscServer {
         Ok(())
     }
 
+    /// タイマー設定を丸め込んで反映し、保存・通知・VRC側への同期を行う
+    fn update_timer_setting(&self, state: &mut AppState, setting: TimerSetting, value: i64) {
+        let clamped = setting.clamp(value);
+        setting.apply_to_state(state, clamped);
+
+        // 設定を保存・通知
+        if let Err(e) = self.update_and_notify_settings(|settings| {
+            setting.apply_to_settings(settings, clamped);
+        }) {
+            eprintln!("Failed to update timer setting: {}", e);
+        }
+
+        // 丸め込み後の値をVRC側に再送信（増減ボタンの場合も表示を同期する）
+        let state_clone = self.state.clone();
+        tokio::spawn(async move {
+            if let Err(e) = send_osc_to_vrchat(
+                setting.address(),
+                vec![OscType::Int(clamped as i32)],
+                &state_clone,
+            )
+            .await
+            {
+                eprintln!("Failed to sync {} to VRC: {}", setting.address(), e);
+            }
+        });
+    }
+
     // OSCサーバーを起動
     pub async fn start(&self, port: u16) -> Result<(), Box<dyn std::error::Error>> {
         let addr = format!("127.0.0.1:{}", port);
@@ -91,6 +210,21 @@ impl OscServer {
         })
     }
 
+    // カウントダウンを開始（0分の場合はキャンセル）
+    fn spawn_countdown(&self, minutes: u32) {
+        let state_clone = self.state.clone();
+        let timer_mgr_clone = self.timer_manager.clone();
+        tokio::spawn(async move {
+            if minutes == 0 {
+                cancel_countdown(state_clone, timer_mgr_clone).await;
+            } else if let Err(e) =
+                start_countdown(state_clone, timer_mgr_clone, minutes, AlarmSource::Countdown).await
+            {
+                eprintln!("Failed to start countdown: {}", e);
+            }
+        });
+    }
+
     // OSCメッセージを処理
     async fn handle_osc_message(&self, msg: OscMessage) {
         let mut state = self.state.lock().unwrap();
@@ -209,6 +343,11 @@ impl OscServer {
                 if let Some(OscType::Bool(pressed)) = msg.args.first() {
                     if *pressed && state.is_ringing {
                         state.stop_pressed = *pressed;
+                        // 停止の条件を満たしていない場合は無視
+                        if !stop_allowed(&state) {
+                            println!("Stop ignored: dismissal conditions not met yet.");
+                            return;
+                        }
 
                         drop(state);
                         let state_clone = self.state.clone();
@@ -219,6 +358,244 @@ impl OscServer {
                     }
                 }
             }
+            "/avatar/parameters/VelocityMagnitude"
+            | "/avatar/parameters/Upright"
+            | "/avatar/parameters/Grounded"
+            | "/avatar/parameters/AngularY" => {
+                // VRChatの組み込みパラメータから動きの情報を更新
+                match (msg.addr.as_str(), msg.args.first()) {
+                    ("/avatar/parameters/VelocityMagnitude", Some(OscType::Float(value))) => {
+                        state.motion.velocity_magnitude = *value;
+                    }
+                    ("/avatar/parameters/Upright", Some(OscType::Float(value))) => {
+                        state.motion.upright = *value;
+                    }
+                    ("/avatar/parameters/Grounded", Some(OscType::Bool(value))) => {
+                        state.motion.grounded = *value;
+                    }
+                    ("/avatar/parameters/AngularY", Some(OscType::Float(value))) => {
+                        state.motion.angular_y = *value;
+                    }
+                    _ => return,
+                }
+                let now = Utc::now();
+                state.motion.last_update = Some(now);
+
+                // 鳴動中は動きによる停止条件の達成を判定
+                if update_movement_progress(&mut state, now) {
+                    println!("Movement dismissal condition met.");
+                    // AFK中は寝ているとみなし、設定に応じて自動停止しない
+                    let afk_blocked = load_settings().presence.blocks_auto_stop(
+                        state.alarm_source,
+                        state.is_afk,
+                        state.snooze_count,
+                        state.max_snoozes,
+                    );
+                    let auto_stop = matches!(
+                        state.movement_dismiss,
+                        Some(ref settings) if settings.mode == MovementDismissMode::AutoStop
+                    ) && stop_allowed(&state)
+                        && !afk_blocked;
+
+                    drop(state);
+                    let state_clone = self.state.clone();
+                    tokio::spawn(async move {
+                        send_movement_ready(&state_clone, true).await;
+                    });
+                    if auto_stop {
+                        handle_timer_event_sync(
+                            self.state.clone(),
+                            self.timer_manager.clone(),
+                            TimerEvent::Stop,
+                        );
+                    }
+                }
+            }
+            "/avatar/parameters/AFK" => {
+                // AFK状態を更新
+                if let Some(OscType::Bool(is_afk)) = msg.args.first() {
+                    state.is_afk = *is_afk;
+                }
+            }
+            "/avatar/parameters/VRMode" => {
+                // VRモードかどうかを更新（VRChatはIntで送信する）
+                match msg.args.first() {
+                    Some(OscType::Int(mode)) => state.vr_mode = Some(*mode != 0),
+                    Some(OscType::Bool(mode)) => state.vr_mode = Some(*mode),
+                    _ => {}
+                }
+            }
+            "/avatar/parameters/CountdownMinutes" => {
+                // ラジアルメニューで選択したカウントダウンの分数を保持
+                if let Some(OscType::Float(value)) = msg.args.first() {
+                    state.countdown_selected_minutes = vrc_float_to_countdown_minutes(*value);
+                }
+            }
+            "/avatar/parameters/CountdownStart" => {
+                // 選択中の分数でカウントダウンを開始（0分の場合はキャンセル）
+                if let Some(OscType::Bool(true)) = msg.args.first() {
+                    let minutes = state.countdown_selected_minutes;
+                    drop(state);
+                    self.spawn_countdown(minutes);
+                }
+            }
+            "/avatar/parameters/CountdownPreset" => {
+                // プリセット番号のカウントダウンを開始（0の場合はキャンセル）
+                if let Some(OscType::Int(slot)) = msg.args.first() {
+                    let minutes = load_settings().countdown_timer.clamped().preset(*slot);
+                    if minutes.is_none() && *slot != 0 {
+                        eprintln!("Unknown countdown preset: {}", slot);
+                        return;
+                    }
+                    drop(state);
+                    self.spawn_countdown(minutes.unwrap_or(0));
+                }
+            }
+            "/avatar/parameters/StopwatchStart"
+            | "/avatar/parameters/StopwatchStop"
+            | "/avatar/parameters/StopwatchLap"
+            | "/avatar/parameters/StopwatchReset" => {
+                // ボタンが押された時のみストップウォッチを操作
+                if let (Some(action), Some(OscType::Bool(true))) =
+                    (StopwatchAction::from_address(&msg.addr), msg.args.first())
+                {
+                    drop(state);
+                    tokio::spawn(apply_stopwatch_action(
+                        self.state.clone(),
+                        self.timer_manager.clone(),
+                        action,
+                    ));
+                }
+            }
+            "/avatar/parameters/PomodoroStart"
+            | "/avatar/parameters/PomodoroStop"
+            | "/avatar/parameters/PomodoroSkip" => {
+                // ボタンが押された時のみポモドーロを操作
+                if let (Some(action), Some(OscType::Bool(true))) =
+                    (PomodoroAction::from_address(&msg.addr), msg.args.first())
+                {
+                    drop(state);
+                    let state_clone = self.state.clone();
+                    let timer_mgr_clone = self.timer_manager.clone();
+                    tokio::spawn(async move {
+                        if let Err(e) =
+                            apply_pomodoro_action(state_clone, timer_mgr_clone, action).await
+                        {
+                            eprintln!("Failed to apply pomodoro action: {}", e);
+                        }
+                    });
+                }
+            }
+            "/avatar/parameters/NapStart" => {
+                // 既定のプリセットで仮眠を開始
+                if let Some(OscType::Bool(true)) = msg.args.first() {
+                    drop(state);
+                    let settings = load_settings().nap.clamped();
+                    let state_clone = self.state.clone();
+                    let timer_mgr_clone = self.timer_manager.clone();
+                    tokio::spawn(async move {
+                        if let Err(e) =
+                            start_nap(state_clone, timer_mgr_clone, &settings, None).await
+                        {
+                            eprintln!("Failed to start nap: {}", e);
+                        }
+                    });
+                }
+            }
+            "/avatar/parameters/SkipNextAlarm" => {
+                // ONで次のアラームをスキップ、ONからOFFに変わった時だけスキップを取り消し
+                // （アバターの読み込み直しで送られるOFFではアプリで設定したスキップを消さない）
+                let previous = state.skip_next_param;
+                if let Some(OscType::Bool(skip)) =
+                    msg.args.first().filter(|arg| **arg == OscType::Bool(true) || previous)
+                {
+                    let skip = *skip;
+                    state.skip_next_param = skip;
+                    drop(state);
+                    let state_clone = self.state.clone();
+                    let timer_mgr_clone = self.timer_manager.clone();
+                    tokio::spawn(async move {
+                        let result = if skip {
+                            skip_next_alarm(state_clone, timer_mgr_clone).await.map(|_| ())
+                        } else {
+                            cancel_skip_next_alarm(state_clone, timer_mgr_clone).await
+                        };
+                        if let Err(e) = result {
+                            eprintln!("Failed to update alarm skip: {}", e);
+                        }
+                    });
+                }
+            }
+            "/avatar/parameters/PauseDays" => {
+                // 今日から指定した日数だけアラームを休止（0で取り消し）
+                if let Some(OscType::Int(days)) = msg.args.first() {
+                    let days = (*days).max(0) as u32;
+                    drop(state);
+                    let state_clone = self.state.clone();
+                    let timer_mgr_clone = self.timer_manager.clone();
+                    tokio::spawn(async move {
+                        if let Err(e) = pause_alarms(state_clone, timer_mgr_clone, days).await {
+                            eprintln!("Failed to update alarm pause: {}", e);
+                        }
+                    });
+                }
+            }
+            "/avatar/parameters/SleepStart" => {
+                // 睡眠セッションの記録を開始
+                if let Some(OscType::Bool(true)) = msg.args.first() {
+                    if let Err(e) = start_session(&mut state) {
+                        eprintln!("Failed to start sleep session: {}", e);
+                    }
+                }
+            }
+            "/avatar/parameters/ChallengeAnswer" => {
+                // 停止チャレンジの回答
+                if let Some(OscType::Int(answer)) = msg.args.first() {
+                    let is_ringing = state.is_ringing;
+                    let solved_challenge = match state.challenge.as_mut() {
+                        Some(challenge)
+                            if is_ringing && !challenge.solved && challenge.answer() == *answer =>
+                        {
+                            challenge.solved = true;
+                            Some(challenge.clone())
+                        }
+                        _ => None,
+                    };
+
+                    // 正解した場合は出題を終了し、停止を受け付ける
+                    if let Some(challenge) = solved_challenge {
+                        println!("Challenge solved. Stop is now accepted.");
+                        let state_clone = self.state.clone();
+                        tokio::spawn(async move {
+                            send_challenge(&state_clone, &challenge).await;
+                        });
+                    }
+                }
+            }
+            "/avatar/parameters/AlarmSetMaxSnoozes"
+            | "/avatar/parameters/AlarmSetRingingDuration"
+            | "/avatar/parameters/AlarmSetSnoozeDuration" => {
+                // タイマー設定を絶対値で設定
+                if let (Some(setting), Some(OscType::Int(value))) =
+                    (TimerSetting::from_address(&msg.addr), msg.args.first())
+                {
+                    self.update_timer_setting(&mut state, setting, *value as i64);
+                }
+            }
+            "/avatar/parameters/MaxSnoozesUp"
+            | "/avatar/parameters/MaxSnoozesDown"
+            | "/avatar/parameters/RingingDurationUp"
+            | "/avatar/parameters/RingingDurationDown"
+            | "/avatar/parameters/SnoozeDurationUp"
+            | "/avatar/parameters/SnoozeDurationDown" => {
+                // 増減ボタンが押された時のみタイマー設定を変更
+                if let (Some((setting, step)), Some(OscType::Bool(true))) =
+                    (TimerSetting::from_step_address(&msg.addr), msg.args.first())
+                {
+                    let current = setting.current(&state) as i64;
+                    self.update_timer_setting(&mut state, setting, current + step);
+                }
+            }
             _ => {
                 // Unknown message - ignore silently
             }
@@ -231,24 +608,16 @@ pub async fn send_heartbeat_to_vrchat(
     state: &AppStateMutex,
     settings: &crate::types::AlarmSettings,
 ) -> Result<(), String> {
-    use crate::utils::{hour_to_vrc_float, minute_to_vrc_float};
-    
-    let target_ip = "127.0.0.1";
-    let target_port = 9000;
-
-    let target: SocketAddr = format!("{}:{}", target_ip, target_port)
-        .parse()
-        .map_err(|e| format!("Invalid target address: {}", e))?;
-
-    let client_socket = UdpSocket::bind("0.0.0.0:0")
-        .await
-        .map_err(|e| format!("Failed to bind client socket: {}", e))?;
+    use crate::utils::{
+        hour_to_vrc_float, minute_to_vrc_float, minutes_until_to_vrc_float,
+        remaining_ratio_to_vrc_float,
+    };
 
     // 複数のOSCメッセージをバンドルとして作成
     let hour_vrc = hour_to_vrc_float(settings.alarm_hour);
     let minute_vrc = minute_to_vrc_float(settings.alarm_minute);
     
-    let messages = vec![
+    let mut messages = vec![
         OscMessage {
             addr: "/avatar/parameters/AlarmSetHour".to_string(),
             args: vec![OscType::Float(hour_vrc)],
@@ -261,8 +630,73 @@ pub async fn send_heartbeat_to_vrchat(
             addr: "/avatar/parameters/AlarmIsOn".to_string(),
             args: vec![OscType::Bool(settings.alarm_is_on)],
         },
+        OscMessage {
+            addr: "/avatar/parameters/AlarmSetMaxSnoozes".to_string(),
+            args: vec![OscType::Int(settings.max_snoozes as i32)],
+        },
+        OscMessage {
+            addr: "/avatar/parameters/AlarmSetRingingDuration".to_string(),
+            args: vec![OscType::Int(settings.ringing_duration_minutes as i32)],
+        },
+        OscMessage {
+            addr: "/avatar/parameters/AlarmSetSnoozeDuration".to_string(),
+            args: vec![OscType::Int(settings.snooze_duration_minutes as i32)],
+        },
     ];
 
+    // 次回アラームまでの残り時間とスヌーズ残り時間を追加
+    if settings.send_countdown_parameters {
+        let info = next_fire_info(state)?;
+        let (snooze_total_seconds, countdown_total_seconds) = {
+            let app_state = state
+                .lock()
+                .map_err(|e| format!("Failed to lock state: {}", e))?;
+            (
+                app_state.current_snooze_minutes as i64 * 60,
+                app_state.countdown_total_minutes as i64 * 60,
+            )
+        };
+        messages.push(OscMessage {
+            addr: "/avatar/parameters/AlarmMinutesUntil".to_string(),
+            args: vec![OscType::Float(minutes_until_to_vrc_float(
+                info.minutes_until_alarm.unwrap_or(0),
+            ))],
+        });
+        messages.push(OscMessage {
+            addr: "/avatar/parameters/SnoozeRemaining".to_string(),
+            args: vec![OscType::Float(remaining_ratio_to_vrc_float(
+                info.snooze_remaining_seconds.unwrap_or(0),
+                snooze_total_seconds,
+            ))],
+        });
+        messages.push(OscMessage {
+            addr: "/avatar/parameters/CountdownRemaining".to_string(),
+            args: vec![OscType::Float(remaining_ratio_to_vrc_float(
+                info.countdown_remaining_seconds.unwrap_or(0),
+                countdown_total_seconds,
+            ))],
+        });
+    }
+
+    send_osc_bundle_to_vrchat(messages, state).await
+}
+
+// 複数のOSCメッセージをバンドルとしてVRChatに送信
+pub async fn send_osc_bundle_to_vrchat(
+    messages: Vec<OscMessage>,
+    state: &AppStateMutex,
+) -> Result<(), String> {
+    let target_ip = "127.0.0.1";
+    let target_port = 9000;
+
+    let target: SocketAddr = format!("{}:{}", target_ip, target_port)
+        .parse()
+        .map_err(|e| format!("Invalid target address: {}", e))?;
+
+    let client_socket = UdpSocket::bind("0.0.0.0:0")
+        .await
+        .map_err(|e| format!("Failed to bind client socket: {}", e))?;
+
     // OSCバンドルとしてパケージング
     let bundle = rosc::OscBundle {
         timetag: rosc::OscTime { seconds: 0, fractional: 1 }, // 即座に実行
@@ -0,0 +1,275 @@
+use crate::config::load_settings;
+use crate::osc::send_osc_bundle_to_vrchat;
+use crate::timer::handle_timer_event;
+use crate::types::{AlarmSource, AppStateMutex, EventKind, TimerEvent, TimerManagerMutex};
+use chrono::{DateTime, Utc};
+use rosc::{OscMessage, OscType};
+use serde::{Deserialize, Serialize};
+use tokio::time::{sleep_until, Duration, Instant};
+
+/// ポモドーロの設定
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PomodoroSettings {
+    pub work_minutes: u32,            // 作業時間
+    pub short_break_minutes: u32,     // 短い休憩の時間
+    pub long_break_minutes: u32,      // 長い休憩の時間
+    pub cycles_until_long_break: u32, // 長い休憩までの作業回数
+    pub ring_seconds: u32,            // フェーズ切り替え時に鳴らす秒数
+}
+
+impl Default for PomodoroSettings {
+    fn default() -> Self {
+        Self {
+            work_minutes: 25,
+            short_break_minutes: 5,
+            long_break_minutes: 15,
+            cycles_until_long_break: 4,
+            ring_seconds: 5,
+        }
+    }
+}
+
+impl PomodoroSettings {
+    // 各値を有効範囲に丸め込み
+    pub fn clamped(self) -> Self {
+        Self {
+            work_minutes: self.work_minutes.clamp(1, 120),
+            short_break_minutes: self.short_break_minutes.clamp(1, 60),
+            long_break_minutes: self.long_break_minutes.clamp(1, 120),
+            cycles_until_long_break: self.cycles_until_long_break.clamp(1, 12),
+            ring_seconds: self.ring_seconds.clamp(1, 60),
+        }
+    }
+}
+
+/// ポモドーロのフェーズ（PomodoroPhaseパラメータの値に対応）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PomodoroPhase {
+    #[default]
+    Idle, // 0: 停止中
+    Work,       // 1: 作業
+    ShortBreak, // 2: 短い休憩
+    LongBreak,  // 3: 長い休憩
+}
+
+impl PomodoroPhase {
+    fn parameter_value(self) -> i32 {
+        match self {
+            Self::Idle => 0,
+            Self::Work => 1,
+            Self::ShortBreak => 2,
+            Self::LongBreak => 3,
+        }
+    }
+
+    fn minutes(self, settings: &PomodoroSettings) -> u32 {
+        match self {
+            Self::Idle => 0,
+            Self::Work => settings.work_minutes,
+            Self::ShortBreak => settings.short_break_minutes,
+            Self::LongBreak => settings.long_break_minutes,
+        }
+    }
+
+    // 次のフェーズと完了した作業回数を取得
+    fn next(self, completed_work: u32, settings: &PomodoroSettings) -> (Self, u32) {
+        match self {
+            Self::Work => {
+                let completed_work = completed_work + 1;
+                if completed_work >= settings.cycles_until_long_break {
+                    (Self::LongBreak, completed_work)
+                } else {
+                    (Self::ShortBreak, completed_work)
+                }
+            }
+            Self::ShortBreak => (Self::Work, completed_work),
+            Self::LongBreak => (Self::Work, 0),
+            Self::Idle => (Self::Work, 0),
+        }
+    }
+}
+
+/// ポモドーロの状態
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PomodoroState {
+    pub phase: PomodoroPhase,                 // 現在のフェーズ
+    pub completed_work: u32,                  // 長い休憩までに完了した作業回数
+    pub phase_ends_at: Option<DateTime<Utc>>, // 現在のフェーズの終了時刻
+}
+
+/// ポモドーロの操作
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PomodoroAction {
+    Start,
+    Stop,
+    Skip,
+}
+
+impl PomodoroAction {
+    /// アバターパラメータのアドレスから取得
+    pub fn from_address(addr: &str) -> Option<Self> {
+        match addr {
+            "/avatar/parameters/PomodoroStart" => Some(Self::Start),
+            "/avatar/parameters/PomodoroStop" => Some(Self::Stop),
+            "/avatar/parameters/PomodoroSkip" => Some(Self::Skip),
+            _ => None,
+        }
+    }
+}
+
+// ポモドーロを操作し、現在の状態を返す
+pub async fn apply_pomodoro_action(
+    state: AppStateMutex,
+    timer_manager: TimerManagerMutex,
+    action: PomodoroAction,
+) -> Result<PomodoroState, String> {
+    let current = state
+        .lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?
+        .pomodoro
+        .clone();
+
+    match action {
+        PomodoroAction::Start => {
+            // 開始済みの場合は最初からやり直す
+            run_from(&state, &timer_manager, PomodoroPhase::Work, 0)?;
+        }
+        PomodoroAction::Skip => {
+            if current.phase == PomodoroPhase::Idle {
+                return Err("Pomodoro is not running".to_string());
+            }
+            let settings = load_settings().pomodoro.clamped();
+            let (phase, completed_work) = current.phase.next(current.completed_work, &settings);
+            run_from(&state, &timer_manager, phase, completed_work)?;
+        }
+        PomodoroAction::Stop => {
+            timer_manager
+                .lock()
+                .map_err(|e| format!("Failed to lock timer manager: {}", e))?
+                .cancel_pomodoro_timer();
+            state
+                .lock()
+                .map_err(|e| format!("Failed to lock state: {}", e))?
+                .pomodoro = PomodoroState::default();
+            send_pomodoro_phase(&state).await;
+        }
+    }
+    println!("Pomodoro: {:?}", action);
+
+    let app_state = state
+        .lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    Ok(app_state.pomodoro.clone())
+}
+
+// 指定したフェーズからポモドーロのサイクルを開始
+fn run_from(
+    state: &AppStateMutex,
+    timer_manager: &TimerManagerMutex,
+    phase: PomodoroPhase,
+    completed_work: u32,
+) -> Result<(), String> {
+    let settings = load_settings().pomodoro.clamped();
+    begin_phase(state, phase, completed_work, &settings)?;
+
+    let state_clone = state.clone();
+    let timer_manager_clone = timer_manager.clone();
+    let pomodoro_handle = tokio::spawn(async move {
+        let (mut phase, mut completed_work) = (phase, completed_work);
+        let mut phase_end = Instant::now() + phase_duration(phase, &settings);
+        send_pomodoro_phase(&state_clone).await;
+        loop {
+            sleep_until(phase_end).await;
+
+            // 次のフェーズに切り替えてから、短く鳴らして知らせる（鳴らす時間もフェーズに含める）
+            (phase, completed_work) = phase.next(completed_work, &settings);
+            phase_end = Instant::now() + phase_duration(phase, &settings);
+            if let Err(e) = begin_phase(&state_clone, phase, completed_work, &settings) {
+                eprintln!("Failed to advance pomodoro: {}", e);
+                return;
+            }
+            println!("Pomodoro phase: {:?}", phase);
+            send_pomodoro_phase(&state_clone).await;
+            ring_transition(&state_clone, &timer_manager_clone, phase).await;
+        }
+    });
+    timer_manager
+        .lock()
+        .map_err(|e| format!("Failed to lock timer manager: {}", e))?
+        .set_pomodoro_timer(pomodoro_handle);
+    Ok(())
+}
+
+fn phase_duration(phase: PomodoroPhase, settings: &PomodoroSettings) -> Duration {
+    Duration::from_secs(phase.minutes(settings) as u64 * 60)
+}
+
+// フェーズの開始を状態に記録
+fn begin_phase(
+    state: &AppStateMutex,
+    phase: PomodoroPhase,
+    completed_work: u32,
+    settings: &PomodoroSettings,
+) -> Result<(), String> {
+    let minutes = phase.minutes(settings) as i64;
+    let mut app_state = state
+        .lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    app_state.pomodoro = PomodoroState {
+        phase,
+        completed_work,
+        phase_ends_at: Some(Utc::now() + chrono::Duration::minutes(minutes)),
+    };
+    Ok(())
+}
+
+// フェーズ切り替えを合図のアラームとして短く鳴らす
+// （おやすみ時間・AFK/VRモード・強度の段階的な上昇はアラームと同じ処理で扱う）
+async fn ring_transition(
+    state: &AppStateMutex,
+    timer_manager: &TimerManagerMutex,
+    phase: PomodoroPhase,
+) {
+    match state.lock() {
+        Ok(mut app_state) => {
+            // アラームの鳴動中・スヌーズ中は、その状態を上書きしないよう鳴らさない
+            if app_state.is_ringing || app_state.snooze_until.is_some() {
+                return;
+            }
+            app_state.alarm_source = AlarmSource::Pomodoro;
+            app_state.snooze_count = 0;
+            app_state.log_event(EventKind::AlarmFired, format!("Pomodoro: {:?}", phase));
+        }
+        Err(e) => {
+            eprintln!("Failed to lock state: {}", e);
+            return;
+        }
+    }
+    handle_timer_event(state.clone(), timer_manager.clone(), TimerEvent::AlarmFire).await;
+}
+
+// 現在のフェーズと作業回数をVRChatに送信
+async fn send_pomodoro_phase(state: &AppStateMutex) {
+    let pomodoro = match state.lock() {
+        Ok(app_state) => app_state.pomodoro.clone(),
+        Err(e) => {
+            eprintln!("Failed to lock state: {}", e);
+            return;
+        }
+    };
+    let messages = vec![
+        OscMessage {
+            addr: "/avatar/parameters/PomodoroPhase".to_string(),
+            args: vec![OscType::Int(pomodoro.phase.parameter_value())],
+        },
+        OscMessage {
+            addr: "/avatar/parameters/PomodoroCycle".to_string(),
+            args: vec![OscType::Int(pomodoro.completed_work as i32)],
+        },
+    ];
+    if let Err(e) = send_osc_bundle_to_vrchat(messages, state).await {
+        eprintln!("Failed to send pomodoro parameters: {}", e);
+    }
+}
@@ -0,0 +1,84 @@
+use crate::types::AlarmSource;
+use serde::{Deserialize, Serialize};
+
+// AFK中に自動停止を見送るスヌーズ回数の上限（最大スヌーズ回数に対する倍率）
+const AFK_SNOOZE_LIMIT_FACTOR: u32 = 2;
+
+/// AFK・VRモードに応じたアラームの動作
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PresenceRules {
+    pub suppress_in_desktop: bool, // デスクトップモードの場合は鳴らさない
+    pub escalate_when_afk: bool,   // AFK中はアラームの強さを上限にする
+}
+
+/// アラームの種類ごとのAFK・VRモードに応じた動作
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PresenceSettings {
+    pub wake: PresenceRules,      // 時刻指定の起床アラーム
+    pub countdown: PresenceRules, // カウントダウンタイマー
+    pub nap: PresenceRules,       // 仮眠
+    pub calendar: PresenceRules,  // .icsファイルから読み込んだ予定
+    pub pomodoro: PresenceRules,  // ポモドーロのフェーズ切り替えの合図
+    // AFK中は寝ているとみなし自動停止しない（起床アラームのみが対象のため種類ごとの設定とは別）
+    pub afk_blocks_auto_stop: bool,
+}
+
+impl PresenceSettings {
+    // AFKのために自動停止を見送るかどうか（起床アラームのみ）
+    // 最大スヌーズ回数の2倍を超えた場合はAFK中でも停止する
+    pub fn blocks_auto_stop(
+        &self,
+        source: AlarmSource,
+        is_afk: bool,
+        snooze_count: u32,
+        max_snoozes: u32,
+    ) -> bool {
+        source == AlarmSource::Wake
+            && self.afk_blocks_auto_stop
+            && is_afk
+            && snooze_count <= max_snoozes.saturating_mul(AFK_SNOOZE_LIMIT_FACTOR)
+    }
+
+    // アラームの種類に対応する動作を取得
+    pub fn for_source(&self, source: AlarmSource) -> &PresenceRules {
+        match source {
+            AlarmSource::Wake => &self.wake,
+            AlarmSource::Countdown => &self.countdown,
+            AlarmSource::Nap => &self.nap,
+            AlarmSource::Calendar => &self.calendar,
+            AlarmSource::Pomodoro => &self.pomodoro,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn afk_rules() -> PresenceSettings {
+        PresenceSettings {
+            afk_blocks_auto_stop: true,
+            ..PresenceSettings::default()
+        }
+    }
+
+    #[test]
+    fn auto_stop_is_blocked_only_for_wake_alarms() {
+        let rules = afk_rules();
+        assert!(rules.blocks_auto_stop(AlarmSource::Wake, true, 4, 3));
+        assert!(!rules.blocks_auto_stop(AlarmSource::Wake, false, 4, 3));
+        assert!(!rules.blocks_auto_stop(AlarmSource::Countdown, true, 4, 3));
+        assert!(!rules.blocks_auto_stop(AlarmSource::Nap, true, 4, 3));
+        assert!(!PresenceSettings::default().blocks_auto_stop(AlarmSource::Wake, true, 4, 3));
+    }
+
+    #[test]
+    fn afk_snoozes_are_capped() {
+        let rules = afk_rules();
+        assert!(rules.blocks_auto_stop(AlarmSource::Wake, true, 6, 3));
+        assert!(!rules.blocks_auto_stop(AlarmSource::Wake, true, 7, 3));
+        assert!(!rules.blocks_auto_stop(AlarmSource::Wake, true, 1, 0));
+    }
+}
@@ -0,0 +1,191 @@
+use chrono::{DateTime, Local, NaiveTime, TimeZone};
+use serde::{Deserialize, Serialize};
+
+/// おやすみ時間中の通知の扱い
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuietHoursMode {
+    /// おやすみ時間が終わるまで遅らせる
+    #[default]
+    Defer,
+    /// 破棄する
+    Drop,
+}
+
+/// おやすみ時間（通知を抑制する時間帯）の設定
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QuietHoursSettings {
+    pub enabled: bool,             // おやすみ時間を有効にするかどうか
+    pub start_hour: u32,           // 開始時
+    pub start_minute: u32,         // 開始分
+    pub end_hour: u32,             // 終了時
+    pub end_minute: u32,           // 終了分
+    pub mode: QuietHoursMode,      // 抑制した通知の扱い
+    pub wake_alarm_critical: bool, // 起床アラームはおやすみ時間中でも鳴らすかどうか
+}
+
+impl Default for QuietHoursSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            start_hour: 23,
+            start_minute: 0,
+            end_hour: 7,
+            end_minute: 0,
+            mode: QuietHoursMode::Defer,
+            wake_alarm_critical: true,
+        }
+    }
+}
+
+/// おやすみ時間の判定結果
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum QuietDecision {
+    Allow,
+    Defer(DateTime<Local>),
+    Drop,
+}
+
+impl QuietHoursSettings {
+    // 各値を有効範囲に丸め込み
+    pub fn clamped(self) -> Self {
+        Self {
+            start_hour: self.start_hour.min(23),
+            start_minute: self.start_minute.min(59),
+            end_hour: self.end_hour.min(23),
+            end_minute: self.end_minute.min(59),
+            ..self
+        }
+    }
+
+    // 指定時刻がおやすみ時間中であれば、その終了時刻を返す
+    pub fn quiet_until(&self, now: DateTime<Local>) -> Option<DateTime<Local>> {
+        if !self.enabled {
+            return None;
+        }
+        let start = NaiveTime::from_hms_opt(self.start_hour, self.start_minute, 0)?;
+        let end = NaiveTime::from_hms_opt(self.end_hour, self.end_minute, 0)?;
+        let time = now.time();
+
+        // 終了日のオフセット（日付をまたぐ場合は開始側なら翌日に終了）
+        let end_day_offset = if start == end {
+            return None;
+        } else if start < end {
+            if time < start || time >= end {
+                return None;
+            }
+            0
+        } else if time >= start {
+            1
+        } else if time < end {
+            0
+        } else {
+            return None;
+        };
+
+        let end_date = now.date_naive() + chrono::Duration::days(end_day_offset);
+        Local
+            .from_local_datetime(&end_date.and_time(end))
+            .earliest()
+    }
+
+    // 通知を送ってよいかを判定（重要な通知はおやすみ時間を無視する）
+    pub fn decide(&self, critical: bool, now: DateTime<Local>) -> QuietDecision {
+        if critical {
+            return QuietDecision::Allow;
+        }
+        match (self.quiet_until(now), self.mode) {
+            (None, _) => QuietDecision::Allow,
+            (Some(until), QuietHoursMode::Defer) => QuietDecision::Defer(until),
+            (Some(_), QuietHoursMode::Drop) => QuietDecision::Drop,
+        }
+    }
+
+    // 重要でない通知を指定した時刻より前に送信できるかどうか（遅らせると間に合わない場合はfalse）
+    pub fn delivers_before(&self, now: DateTime<Local>, deadline: DateTime<Local>) -> bool {
+        match self.decide(false, now) {
+            QuietDecision::Allow => now < deadline,
+            QuietDecision::Defer(until) => until < deadline,
+            QuietDecision::Drop => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local(day: u32, hour: u32, minute: u32) -> DateTime<Local> {
+        Local
+            .with_ymd_and_hms(2026, 10, day, hour, minute, 0)
+            .unwrap()
+    }
+
+    // 23:00から翌7:00までのおやすみ時間
+    fn overnight() -> QuietHoursSettings {
+        QuietHoursSettings {
+            enabled: true,
+            ..QuietHoursSettings::default()
+        }
+    }
+
+    #[test]
+    fn overnight_quiet_hours_span_midnight() {
+        let settings = overnight();
+        assert_eq!(settings.quiet_until(local(19, 22, 59)), None);
+        assert_eq!(
+            settings.quiet_until(local(19, 23, 0)),
+            Some(local(20, 7, 0))
+        );
+        assert_eq!(
+            settings.quiet_until(local(19, 23, 59)),
+            Some(local(20, 7, 0))
+        );
+        assert_eq!(settings.quiet_until(local(20, 0, 0)), Some(local(20, 7, 0)));
+        assert_eq!(
+            settings.quiet_until(local(20, 6, 59)),
+            Some(local(20, 7, 0))
+        );
+        assert_eq!(settings.quiet_until(local(20, 7, 0)), None);
+    }
+
+    #[test]
+    fn decide_follows_mode_and_criticality() {
+        let settings = overnight();
+        assert_eq!(
+            settings.decide(false, local(20, 0, 0)),
+            QuietDecision::Defer(local(20, 7, 0))
+        );
+        assert_eq!(settings.decide(true, local(20, 0, 0)), QuietDecision::Allow);
+        assert_eq!(
+            settings.decide(false, local(20, 7, 0)),
+            QuietDecision::Allow
+        );
+
+        let settings = QuietHoursSettings {
+            mode: QuietHoursMode::Drop,
+            ..overnight()
+        };
+        assert_eq!(
+            settings.decide(false, local(19, 23, 59)),
+            QuietDecision::Drop
+        );
+    }
+
+    #[test]
+    fn pre_notice_deferred_past_alarm_is_dropped() {
+        let settings = overnight();
+        // 6:30のアラームの10分前の予告は7:00まで遅れるため送信しない
+        assert!(!settings.delivers_before(local(20, 6, 20), local(20, 6, 30)));
+        // 7:30のアラームの予告は7:00に送信すれば間に合う
+        assert!(settings.delivers_before(local(20, 6, 50), local(20, 7, 30)));
+        assert!(settings.delivers_before(local(20, 7, 20), local(20, 7, 30)));
+
+        let settings = QuietHoursSettings {
+            mode: QuietHoursMode::Drop,
+            ..overnight()
+        };
+        assert!(!settings.delivers_before(local(20, 6, 50), local(20, 7, 30)));
+    }
+}
@@ -0,0 +1,103 @@
+use chrono::{DateTime, Datelike, Local, NaiveDate, TimeZone};
+
+// 次のアラームを探索する日数（連休や長期の休暇でスキップが続く場合も考慮する）
+const SCHEDULE_SEARCH_DAYS: i64 = 370;
+
+// 指定した日がアラームを鳴らす曜日かどうか（0=日曜日、6=土曜日、空の場合は毎日）
+pub fn weekday_allowed(weekdays: &[u32], date: NaiveDate) -> bool {
+    weekdays.is_empty() || weekdays.contains(&date.weekday().num_days_from_sunday())
+}
+
+// 曜日の指定を有効範囲に丸め込み、重複を除いて並べ替え
+pub fn normalize_weekdays(weekdays: Vec<u32>) -> Vec<u32> {
+    let mut weekdays: Vec<u32> = weekdays.into_iter().filter(|day| *day <= 6).collect();
+    weekdays.sort_unstable();
+    weekdays.dedup();
+    weekdays
+}
+
+// 指定した日の時刻を作成（夏時間の切り替えで存在しない時刻の場合はNone）
+pub fn local_time_on(date: NaiveDate, hour: u32, minute: u32) -> Option<DateTime<Local>> {
+    let naive = date.and_hms_opt(hour, minute, 0)?;
+    Local.from_local_datetime(&naive).earliest()
+}
+
+// 指定した日から探索範囲の日付を列挙
+fn search_dates(today: NaiveDate) -> impl Iterator<Item = NaiveDate> {
+    (0..SCHEDULE_SEARCH_DAYS)
+        .filter_map(move |offset| today.checked_add_signed(chrono::Duration::days(offset)))
+}
+
+// 現在時刻より後で、曜日の指定に一致する次のアラーム時刻を計算
+// alarm_atは日ごとのアラーム時刻を返す（その日に鳴らさない場合はNone）
+pub fn next_alarm_time(
+    now: DateTime<Local>,
+    weekdays: &[u32],
+    alarm_at: impl Fn(NaiveDate) -> Option<DateTime<Local>>,
+) -> Option<DateTime<Local>> {
+    search_dates(now.date_naive())
+        .filter(|date| weekday_allowed(weekdays, *date))
+        .filter_map(alarm_at)
+        .find(|candidate| *candidate > now)
+}
+
+// 本来は鳴る予定だったが、祝日・スキップ・休暇のためにスキップされる次の日を取得
+pub fn next_skipped_date(
+    now: DateTime<Local>,
+    weekdays: &[u32],
+    alarm_at: impl Fn(NaiveDate) -> Option<DateTime<Local>>,
+    skipped: impl Fn(NaiveDate) -> bool,
+) -> Option<NaiveDate> {
+    search_dates(now.date_naive())
+        .filter(|date| weekday_allowed(weekdays, *date))
+        .find(|date| skipped(*date) && alarm_at(*date).is_some_and(|at| at > now))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ymd(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    // 毎日7:00のアラーム
+    fn seven_am(date: NaiveDate) -> Option<DateTime<Local>> {
+        local_time_on(date, 7, 0)
+    }
+
+    #[test]
+    fn empty_weekdays_ring_every_day() {
+        // 2026-10-19は月曜日
+        let now = local_time_on(ymd(2026, 10, 19), 6, 0).unwrap();
+        assert_eq!(
+            next_alarm_time(now, &[], seven_am),
+            seven_am(ymd(2026, 10, 19))
+        );
+        let now = local_time_on(ymd(2026, 10, 19), 7, 0).unwrap();
+        assert_eq!(
+            next_alarm_time(now, &[], seven_am),
+            seven_am(ymd(2026, 10, 20))
+        );
+    }
+
+    #[test]
+    fn single_weekday_waits_for_that_day() {
+        let now = local_time_on(ymd(2026, 10, 19), 8, 0).unwrap();
+        // 月曜日の時刻を過ぎた場合は翌週の月曜日
+        assert_eq!(
+            next_alarm_time(now, &[1], seven_am),
+            seven_am(ymd(2026, 10, 26))
+        );
+        // 水曜日のみ
+        assert_eq!(
+            next_alarm_time(now, &[3], seven_am),
+            seven_am(ymd(2026, 10, 21))
+        );
+    }
+
+    #[test]
+    fn weekdays_are_normalized() {
+        assert_eq!(normalize_weekdays(vec![5, 1, 7, 1, 0]), vec![0, 1, 5]);
+    }
+}
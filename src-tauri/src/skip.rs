@@ -0,0 +1,344 @@
+use crate::config::{load_settings, save_settings};
+use crate::osc::send_osc_to_vrchat;
+use crate::timer::reschedule_if_idle;
+use crate::types::{AlarmSettings, AlarmType, AppStateMutex, TimerManagerMutex};
+use chrono::{DateTime, Local, NaiveDate, Utc};
+use rosc::OscType;
+use serde::{Deserialize, Serialize};
+
+/// アラームを鳴らさない期間（開始日・終了日を含む）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct VacationRange {
+    pub start: NaiveDate, // 開始日
+    pub end: NaiveDate,   // 終了日
+}
+
+impl VacationRange {
+    // 期間を検証
+    pub fn validate(&self) -> Result<(), String> {
+        if self.start > self.end {
+            return Err(format!(
+                "Vacation start ({}) must not be later than its end ({})",
+                self.start, self.end
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        (self.start..=self.end).contains(&date)
+    }
+}
+
+/// 次回のスキップと休暇の設定（過ぎた日付は自動的に削除される）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SkipSettings {
+    pub skip_date: Option<NaiveDate>, // 1回だけスキップするアラームの日
+    pub skip_at: Option<DateTime<Utc>>, // cron式のアラームで1回だけスキップする時刻（その日の他の時刻は鳴らす）
+    pub vacations: Vec<VacationRange>,  // アラームを鳴らさない期間
+    pub pause: Option<VacationRange>,   // PauseDaysで設定した休止期間（休暇とは別に管理）
+    pub pause_days: u32,                // 最後に受信したPauseDaysの値（同じ値の再送は無視する）
+}
+
+impl SkipSettings {
+    // 指定した日のアラームを鳴らさないかどうか
+    pub fn is_skipped(&self, date: NaiveDate) -> bool {
+        (self.skip_at.is_none() && self.skip_date == Some(date))
+            || self.ranges().any(|range| range.contains(date))
+    }
+
+    // 休暇とPauseDaysによる休止期間を列挙
+    pub fn ranges(&self) -> impl Iterator<Item = &VacationRange> {
+        self.vacations.iter().chain(self.pause.iter())
+    }
+
+    // 過ぎたスキップと、今日より前に終わった休暇を削除
+    pub fn without_expired(self, now: DateTime<Local>) -> Self {
+        let today = now.date_naive();
+        // cron式のアラームのスキップは、スキップした時刻が過ぎたら削除
+        let skip_passed = self.skip_at.is_some_and(|at| at <= now);
+        let skip_date = self.skip_date.filter(|date| *date >= today && !skip_passed);
+        Self {
+            skip_date,
+            skip_at: skip_date.and(self.skip_at),
+            vacations: self
+                .vacations
+                .into_iter()
+                .filter(|range| range.end >= today)
+                .collect(),
+            // 休止期間が終わってもPauseDaysの値は残し、同じ値の再送で休止し直さない
+            pause: self.pause.filter(|range| range.end >= today),
+            pause_days: self.pause_days,
+        }
+    }
+
+    // PauseDaysの値を反映（値が変わった場合のみ、今日から指定した日数まで休止期間を延長・短縮）
+    // 0の場合は休止期間を取り消し、UIで追加した休暇には触れない
+    pub fn with_pause_days(self, days: u32, today: NaiveDate) -> Self {
+        if days == self.pause_days {
+            return self;
+        }
+        let pause = match days {
+            0 => None,
+            _ => {
+                // 休止中の場合は開始日をそのままにする
+                let start = self
+                    .pause
+                    .filter(|range| range.contains(today))
+                    .map_or(today, |range| range.start);
+                today
+                    .checked_add_signed(chrono::Duration::days(days as i64 - 1))
+                    .map(|end| VacationRange { start, end })
+            }
+        };
+        Self {
+            pause,
+            pause_days: days,
+            ..self
+        }
+    }
+}
+
+// スキップの設定を保存して待機中のアラームに反映
+async fn save_skip(
+    state: AppStateMutex,
+    timer_manager: TimerManagerMutex,
+    skip: SkipSettings,
+) -> Result<(), String> {
+    let settings = AlarmSettings {
+        skip,
+        ..load_settings()
+    };
+    save_settings(&settings)?;
+    reschedule_if_idle(state, timer_manager).await;
+    Ok(())
+}
+
+// 次のアラームを1回スキップし、スキップした日を返す（既にスキップ中の場合はその日を返す）
+pub async fn skip_next_alarm(
+    state: AppStateMutex,
+    timer_manager: TimerManagerMutex,
+) -> Result<NaiveDate, String> {
+    let skip = load_settings().skip;
+    if let Some(date) = skip.skip_date {
+        send_skip_state(&state, true).await;
+        return Ok(date);
+    }
+
+    let next_alarm_at = state
+        .lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?
+        .next_alarm_at;
+    let date = next_alarm_at
+        .ok_or_else(|| "No upcoming alarm to skip".to_string())?
+        .with_timezone(&Local)
+        .date_naive();
+    println!("Skipping the alarm on {}", date);
+
+    // cron式のアラームは1日に複数回鳴るため、次に鳴る時刻だけをスキップ
+    let skip_at = match load_settings().alarm_type {
+        AlarmType::Cron => next_alarm_at,
+        _ => None,
+    };
+    let skip = SkipSettings {
+        skip_date: Some(date),
+        skip_at,
+        ..skip
+    };
+    save_skip(state.clone(), timer_manager, skip).await?;
+    send_skip_state(&state, true).await;
+    Ok(date)
+}
+
+// 次のアラームのスキップを取り消し
+pub async fn cancel_skip_next_alarm(
+    state: AppStateMutex,
+    timer_manager: TimerManagerMutex,
+) -> Result<(), String> {
+    let skip = load_settings().skip;
+    if skip.skip_date.is_none() {
+        return Ok(());
+    }
+    let skip = SkipSettings {
+        skip_date: None,
+        skip_at: None,
+        ..skip
+    };
+    save_skip(state.clone(), timer_manager, skip).await?;
+    send_skip_state(&state, false).await;
+    Ok(())
+}
+
+// スキップの状態をSkipNextAlarmパラメータでアバターに反映
+pub async fn send_skip_state(state: &AppStateMutex, skipped: bool) {
+    match state.lock() {
+        Ok(mut app_state) => app_state.skip_next_param = skipped,
+        Err(e) => {
+            eprintln!("Failed to lock state: {}", e);
+            return;
+        }
+    }
+    if let Err(e) = send_osc_to_vrchat(
+        "/avatar/parameters/SkipNextAlarm",
+        vec![OscType::Bool(skipped)],
+        state,
+    )
+    .await
+    {
+        eprintln!("Failed to send skip state: {}", e);
+    }
+}
+
+// 休暇の期間を追加（重複する期間はそのまま残す）
+pub async fn add_vacation(
+    state: AppStateMutex,
+    timer_manager: TimerManagerMutex,
+    range: VacationRange,
+) -> Result<(), String> {
+    range.validate()?;
+    if range.end < Local::now().date_naive() {
+        return Err(format!("Vacation ending on {} is already over", range.end));
+    }
+
+    let mut skip = load_settings().skip;
+    if !skip.vacations.contains(&range) {
+        skip.vacations.push(range);
+        skip.vacations.sort_by_key(|range| (range.start, range.end));
+    }
+    save_skip(state, timer_manager, skip).await
+}
+
+// 休暇の期間を削除
+pub async fn remove_vacation(
+    state: AppStateMutex,
+    timer_manager: TimerManagerMutex,
+    range: VacationRange,
+) -> Result<(), String> {
+    let mut skip = load_settings().skip;
+    let count = skip.vacations.len();
+    skip.vacations.retain(|existing| *existing != range);
+    if skip.vacations.len() == count {
+        return Err(format!(
+            "Vacation from {} to {} not found",
+            range.start, range.end
+        ));
+    }
+    save_skip(state, timer_manager, skip).await
+}
+
+// PauseDaysの値に応じてアラームを休止（前回と同じ値の場合は何もしない）
+pub async fn pause_alarms(
+    state: AppStateMutex,
+    timer_manager: TimerManagerMutex,
+    days: u32,
+) -> Result<(), String> {
+    let skip = load_settings().skip;
+    if skip.pause_days == days {
+        return Ok(());
+    }
+    let skip = skip.with_pause_days(days, Local::now().date_naive());
+    match skip.pause {
+        Some(range) => println!("Pausing alarms from {} to {}", range.start, range.end),
+        None => println!("Cancelling alarm pause"),
+    }
+    save_skip(state, timer_manager, skip).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::TimeZone;
+
+    fn ymd(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn local(year: i32, month: u32, day: u32, hour: u32) -> DateTime<Local> {
+        let naive = ymd(year, month, day).and_hms_opt(hour, 0, 0).unwrap();
+        Local.from_local_datetime(&naive).unwrap()
+    }
+
+    #[test]
+    fn expired_entries_are_removed() {
+        let skip = SkipSettings {
+            skip_date: Some(ymd(2026, 10, 17)),
+            skip_at: None,
+            pause: Some(VacationRange {
+                start: ymd(2026, 10, 15),
+                end: ymd(2026, 10, 17),
+            }),
+            pause_days: 3,
+            vacations: vec![
+                VacationRange {
+                    start: ymd(2026, 10, 1),
+                    end: ymd(2026, 10, 17),
+                },
+                VacationRange {
+                    start: ymd(2026, 10, 10),
+                    end: ymd(2026, 10, 18),
+                },
+            ],
+        };
+        let skip = skip.without_expired(local(2026, 10, 18, 0));
+        assert_eq!(skip.skip_date, None);
+        assert_eq!(skip.vacations.len(), 1);
+        assert!(skip.is_skipped(ymd(2026, 10, 18)));
+        assert!(!skip.is_skipped(ymd(2026, 10, 19)));
+        assert_eq!(skip.pause, None);
+        assert_eq!(skip.pause_days, 3);
+    }
+
+    #[test]
+    fn cron_skip_expires_after_the_skipped_time() {
+        let skip = SkipSettings {
+            skip_date: Some(ymd(2026, 10, 18)),
+            skip_at: Some(local(2026, 10, 18, 9).with_timezone(&Utc)),
+            ..SkipSettings::default()
+        };
+        // 同じ日の他の時刻は鳴らすため、日付ではスキップしない
+        assert!(!skip.is_skipped(ymd(2026, 10, 18)));
+        assert_eq!(skip.clone().without_expired(local(2026, 10, 18, 8)), skip);
+        assert_eq!(
+            skip.without_expired(local(2026, 10, 18, 9)),
+            SkipSettings::default()
+        );
+    }
+
+    #[test]
+    fn pause_days_only_changes_its_own_range() {
+        let vacation = VacationRange {
+            start: ymd(2026, 10, 10),
+            end: ymd(2026, 10, 31),
+        };
+        let skip = SkipSettings {
+            vacations: vec![vacation],
+            ..SkipSettings::default()
+        };
+        // 既定値と同じ0の再送は何もしない
+        assert_eq!(skip.clone().with_pause_days(0, ymd(2026, 10, 18)), skip);
+
+        let paused = skip.with_pause_days(3, ymd(2026, 10, 18));
+        let pause = VacationRange {
+            start: ymd(2026, 10, 18),
+            end: ymd(2026, 10, 20),
+        };
+        assert_eq!(paused.pause, Some(pause));
+        // 翌日に同じ値を受信しても休止し直さない
+        assert_eq!(paused.clone().with_pause_days(3, ymd(2026, 10, 19)), paused);
+
+        // 休止中に値を変えると開始日はそのままで終了日を変更
+        let extended = paused.with_pause_days(5, ymd(2026, 10, 19));
+        assert_eq!(
+            extended.pause,
+            Some(VacationRange {
+                end: ymd(2026, 10, 23),
+                ..pause
+            })
+        );
+        let cancelled = extended.with_pause_days(0, ymd(2026, 10, 19));
+        assert_eq!(cancelled.pause, None);
+        assert_eq!(cancelled.vacations, vec![vacation]);
+    }
+}
@@ -0,0 +1,438 @@
+use crate::config::get_config_dir;
+use crate::types::{AppState, AppStateMutex, EventKind};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use tokio::time::{sleep, Duration};
+
+// 動きのサンプリング間隔
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(30);
+// 寝付いたと判定するために静かな状態が続く必要がある分数
+const SETTLE_MINUTES: i64 = 10;
+// 落ち着きがないと判定する活動量のしきい値
+const RESTLESS_THRESHOLD: f32 = 0.3;
+// 途中経過をファイルに書き出すサンプル数の間隔（約5分ごと）
+const FLUSH_EVERY_SAMPLES: usize = 10;
+
+// 書き込む内容の採番（状態のロック中に採番するため、番号の順序は状態の変化の順序と一致する）
+static SNAPSHOT_SEQUENCE: AtomicU64 = AtomicU64::new(0);
+// 最後に書き込んだ内容の番号（古い途中経過で終了時の内容を上書きしないようにする）
+static LAST_WRITTEN_SEQUENCE: Mutex<u64> = Mutex::new(0);
+
+/// 動きのサンプル
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MotionSample {
+    pub at: DateTime<Utc>,
+    pub velocity_magnitude: f32,
+    pub upright: f32,
+    pub angular_y: f32,
+    pub grounded: bool,
+}
+
+impl MotionSample {
+    // 移動速度と回転速度を合わせた活動量
+    fn activity(&self) -> f32 {
+        self.velocity_magnitude + self.angular_y.abs()
+    }
+}
+
+/// 睡眠セッション
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SleepSession {
+    pub id: String,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: Option<DateTime<Utc>>,
+    pub snooze_count: u32,
+    pub samples: Vec<MotionSample>,
+}
+
+/// 落ち着きのなかった期間
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RestlessPeriod {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub peak_activity: f32,
+}
+
+/// 1晩ごとの睡眠の集計
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SleepReport {
+    pub id: String,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: DateTime<Utc>,
+    pub duration_minutes: i64,
+    pub time_to_fall_asleep_minutes: Option<i64>,
+    pub restless_periods: Vec<RestlessPeriod>,
+    pub restless_minutes: i64,
+    pub snooze_count: u32,
+    pub sample_count: usize,
+}
+
+// 保存するセッションファイルの内容
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SleepRecord {
+    report: SleepReport,
+    session: SleepSession,
+}
+
+/// ファイルに書き込むセッションの内容（状態のロックを解放してから書き込む）
+#[derive(Debug)]
+pub struct SessionSnapshot {
+    sequence: u64,
+    session: SleepSession,
+}
+
+impl SessionSnapshot {
+    fn new(session: SleepSession) -> Self {
+        Self {
+            sequence: SNAPSHOT_SEQUENCE.fetch_add(1, Ordering::Relaxed) + 1,
+            session,
+        }
+    }
+}
+
+impl SleepSession {
+    pub fn new(started_at: DateTime<Utc>) -> Self {
+        Self {
+            id: started_at.format("%Y%m%d-%H%M%S").to_string(),
+            started_at,
+            ended_at: None,
+            snooze_count: 0,
+            samples: Vec::new(),
+        }
+    }
+
+    // セッションの集計を作成
+    pub fn report(&self) -> SleepReport {
+        let ended_at = self.ended_at.unwrap_or_else(Utc::now);
+
+        // 静かな状態が一定時間続いた最初の時点を寝付いた時刻とする
+        let mut quiet_since: Option<DateTime<Utc>> = None;
+        let mut fell_asleep_at = None;
+        for sample in &self.samples {
+            if sample.activity() >= RESTLESS_THRESHOLD {
+                quiet_since = None;
+                continue;
+            }
+            let since = *quiet_since.get_or_insert(sample.at);
+            if sample.at.signed_duration_since(since).num_minutes() >= SETTLE_MINUTES {
+                fell_asleep_at = Some(since);
+                break;
+            }
+        }
+
+        // 寝付いた後で活動量がしきい値を超えた連続区間を集計
+        let mut restless_periods: Vec<RestlessPeriod> = Vec::new();
+        let mut current: Option<RestlessPeriod> = None;
+        let asleep_samples = self
+            .samples
+            .iter()
+            .filter(|sample| fell_asleep_at.is_some_and(|asleep| sample.at >= asleep));
+        for sample in asleep_samples {
+            let activity = sample.activity();
+            if activity >= RESTLESS_THRESHOLD {
+                let period = current.get_or_insert(RestlessPeriod {
+                    start: sample.at,
+                    end: sample.at,
+                    peak_activity: activity,
+                });
+                period.end = sample.at;
+                period.peak_activity = period.peak_activity.max(activity);
+            } else if let Some(period) = current.take() {
+                restless_periods.push(period);
+            }
+        }
+        restless_periods.extend(current);
+
+        // 各区間はサンプリング間隔ぶんの長さを持つものとして合計
+        let interval_seconds = SAMPLE_INTERVAL.as_secs() as i64;
+        let restless_minutes = restless_periods
+            .iter()
+            .map(|period| {
+                period.end.signed_duration_since(period.start).num_seconds() + interval_seconds
+            })
+            .sum::<i64>()
+            / 60;
+
+        SleepReport {
+            id: self.id.clone(),
+            started_at: self.started_at,
+            ended_at,
+            duration_minutes: ended_at
+                .signed_duration_since(self.started_at)
+                .num_minutes(),
+            time_to_fall_asleep_minutes: fell_asleep_at
+                .map(|asleep| asleep.signed_duration_since(self.started_at).num_minutes()),
+            restless_periods,
+            restless_minutes,
+            snooze_count: self.snooze_count,
+            sample_count: self.samples.len(),
+        }
+    }
+
+    // サンプルをCSV形式に変換
+    fn to_csv(&self) -> String {
+        let mut csv = String::from("timestamp,velocity_magnitude,upright,angular_y,grounded\n");
+        for sample in &self.samples {
+            csv.push_str(&format!(
+                "{},{},{},{},{}\n",
+                sample.at.to_rfc3339(),
+                sample.velocity_magnitude,
+                sample.upright,
+                sample.angular_y,
+                sample.grounded
+            ));
+        }
+        csv
+    }
+}
+
+// 睡眠セッションの保存先
+fn sessions_dir() -> PathBuf {
+    let mut path = get_config_dir();
+    path.push("sleep-sessions");
+    path
+}
+
+fn session_path(id: &str) -> Result<PathBuf, String> {
+    // パス区切りなどを含むIDは受け付けない
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        return Err(format!("Invalid sleep session id: {}", id));
+    }
+    let mut path = sessions_dir();
+    path.push(format!("{}.json", id));
+    Ok(path)
+}
+
+// 睡眠セッションを開始
+pub fn start_session(state: &mut AppState) -> Result<(), String> {
+    if state.sleep_session.is_some() {
+        return Err("Sleep session is already running".to_string());
+    }
+    let session = SleepSession::new(Utc::now());
+    println!("Sleep session started: {}", session.id);
+    state.log_event(EventKind::SleepStarted, session.id.clone());
+    state.sleep_session = Some(session);
+    Ok(())
+}
+
+// 睡眠セッションを集計と共にファイルに保存（終了前の途中経過も同じファイルに上書き）
+// ファイルの書き込みは別スレッドで行い、後から採番された内容が書き込み済みの場合は書き込まない
+async fn save_session(snapshot: SessionSnapshot) -> Result<SleepReport, String> {
+    tokio::task::spawn_blocking(move || {
+        let record = SleepRecord {
+            report: snapshot.session.report(),
+            session: snapshot.session,
+        };
+        let mut last_written = LAST_WRITTEN_SEQUENCE
+            .lock()
+            .map_err(|e| format!("Failed to lock sleep session writer: {}", e))?;
+        if snapshot.sequence < *last_written {
+            return Ok(record.report);
+        }
+        let path = session_path(&record.report.id)?;
+        fs::create_dir_all(sessions_dir())
+            .map_err(|e| format!("Failed to create sleep session directory: {}", e))?;
+        let content = serde_json::to_string_pretty(&record)
+            .map_err(|e| format!("Failed to serialize sleep session: {}", e))?;
+        fs::write(&path, content).map_err(|e| format!("Failed to write sleep session: {}", e))?;
+        *last_written = snapshot.sequence;
+        Ok(record.report)
+    })
+    .await
+    .map_err(|e| format!("Failed to save sleep session: {}", e))?
+}
+
+// 睡眠セッションを終了し、保存する内容を取得（保存はロックを解放してからsave_finished_sessionで行う）
+pub fn finish_session(state: &mut AppState) -> Option<SessionSnapshot> {
+    let mut session = state.sleep_session.take()?;
+    session.ended_at = Some(Utc::now());
+    let report = session.report();
+    state.log_event(
+        EventKind::SleepEnded,
+        format!("{} ({} minutes)", report.id, report.duration_minutes),
+    );
+    Some(SessionSnapshot::new(session))
+}
+
+// 終了した睡眠セッションを集計と共に保存
+// 保存に失敗した場合はセッションを継続中に戻し、サンプルを失わないようにする
+pub async fn save_finished_session(
+    state: &AppStateMutex,
+    snapshot: SessionSnapshot,
+) -> Result<SleepReport, String> {
+    let session = snapshot.session.clone();
+    match save_session(snapshot).await {
+        Ok(report) => {
+            println!("Saved sleep session: {}", report.id);
+            Ok(report)
+        }
+        Err(e) => {
+            if let Ok(mut app_state) = state.lock() {
+                if app_state.sleep_session.is_none() {
+                    app_state.sleep_session = Some(SleepSession {
+                        ended_at: None,
+                        ..session
+                    });
+                }
+            }
+            Err(e)
+        }
+    }
+}
+
+// 終了した睡眠セッションを別タスクで保存（アラームの処理を待たせないようにする）
+pub fn spawn_save_finished_session(state: &AppStateMutex, snapshot: Option<SessionSnapshot>) {
+    let Some(snapshot) = snapshot else {
+        return;
+    };
+    let state = state.clone();
+    tokio::spawn(async move {
+        if let Err(e) = save_finished_session(&state, snapshot).await {
+            eprintln!("Failed to finish sleep session: {}", e);
+        }
+    });
+}
+
+// 保存された睡眠セッションの集計を新しい順に取得
+pub fn load_reports() -> Result<Vec<SleepReport>, String> {
+    let entries = match fs::read_dir(sessions_dir()) {
+        Ok(entries) => entries,
+        Err(_) => return Ok(Vec::new()),
+    };
+    let mut reports: Vec<SleepReport> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|entry| fs::read_to_string(entry.path()).ok())
+        .filter_map(|content| serde_json::from_str::<SleepRecord>(&content).ok())
+        .map(|record| record.report)
+        .collect();
+    reports.sort_by_key(|report| std::cmp::Reverse(report.started_at));
+    Ok(reports)
+}
+
+// 睡眠セッションをCSVまたはJSONとして書き出し
+pub fn export_session(id: &str, format: &str, path: &str) -> Result<(), String> {
+    let content = fs::read_to_string(session_path(id)?)
+        .map_err(|e| format!("Failed to read sleep session: {}", e))?;
+    let record: SleepRecord = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse sleep session: {}", e))?;
+
+    let output = match format {
+        "csv" => record.session.to_csv(),
+        "json" => serde_json::to_string_pretty(&record)
+            .map_err(|e| format!("Failed to serialize sleep session: {}", e))?,
+        _ => return Err(format!("Unsupported export format: {}", format)),
+    };
+    fs::write(path, output).map_err(|e| format!("Failed to write export file: {}", e))
+}
+
+// 睡眠セッション中は動きを定期的にサンプリングし、途中経過もファイルに書き出す
+pub async fn run_sleep_sampler(state: AppStateMutex) {
+    loop {
+        sleep(SAMPLE_INTERVAL).await;
+        let snapshot = {
+            let mut app_state = match state.lock() {
+                Ok(state) => state,
+                Err(e) => {
+                    eprintln!("Failed to lock state: {}", e);
+                    return;
+                }
+            };
+            let motion = app_state.motion.clone();
+            let Some(session) = app_state.sleep_session.as_mut() else {
+                continue;
+            };
+            session.samples.push(MotionSample {
+                at: Utc::now(),
+                velocity_magnitude: motion.velocity_magnitude,
+                upright: motion.upright,
+                angular_y: motion.angular_y,
+                grounded: motion.grounded,
+            });
+            // アプリが異常終了しても記録が残るよう、一定間隔で書き出す
+            (session.samples.len() % FLUSH_EVERY_SAMPLES == 0)
+                .then(|| SessionSnapshot::new(session.clone()))
+        };
+        if let Some(snapshot) = snapshot {
+            if let Err(e) = save_session(snapshot).await {
+                eprintln!("Failed to flush sleep session: {}", e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    // 開始から30秒ごとのサンプル（活動量を指定）
+    fn session_with_activity(activity: impl Fn(i64) -> f32) -> SleepSession {
+        let started_at = Utc.with_ymd_and_hms(2026, 10, 18, 23, 0, 0).unwrap();
+        let mut session = SleepSession::new(started_at);
+        session.samples = (0..120)
+            .map(|i| MotionSample {
+                at: started_at + chrono::Duration::seconds(i * 30),
+                velocity_magnitude: activity(i),
+                upright: 0.0,
+                angular_y: 0.0,
+                grounded: true,
+            })
+            .collect();
+        session.ended_at = Some(started_at + chrono::Duration::hours(1));
+        session
+    }
+
+    #[test]
+    fn session_id_is_the_start_time() {
+        let started_at = Utc.with_ymd_and_hms(2026, 10, 18, 23, 5, 9).unwrap();
+        assert_eq!(SleepSession::new(started_at).id, "20261018-230509");
+    }
+
+    #[test]
+    fn report_finds_sleep_onset_and_restless_periods() {
+        // 5分間動いた後に静かになり、20分と40分の時点で落ち着きがなくなる
+        let session = session_with_activity(|i| match i {
+            0..=9 => 1.0,
+            40 | 41 => 0.5,
+            80 => 0.8,
+            _ => 0.0,
+        });
+        let report = session.report();
+        assert_eq!(report.duration_minutes, 60);
+        assert_eq!(report.time_to_fall_asleep_minutes, Some(5));
+        let periods: Vec<(i64, i64, f32)> = report
+            .restless_periods
+            .iter()
+            .map(|period| {
+                (
+                    period
+                        .start
+                        .signed_duration_since(session.started_at)
+                        .num_seconds(),
+                    period
+                        .end
+                        .signed_duration_since(session.started_at)
+                        .num_seconds(),
+                    period.peak_activity,
+                )
+            })
+            .collect();
+        assert_eq!(periods, vec![(1200, 1230, 0.5), (2400, 2400, 0.8)]);
+        // 各区間にサンプリング間隔ぶんを加える（60秒 + 30秒）
+        assert_eq!(report.restless_minutes, 1);
+        assert_eq!(report.sample_count, 120);
+    }
+
+    #[test]
+    fn restless_session_never_falls_asleep() {
+        let report = session_with_activity(|i| if i % 10 == 0 { 1.0 } else { 0.0 }).report();
+        assert_eq!(report.time_to_fall_asleep_minutes, None);
+        assert!(report.restless_periods.is_empty());
+        assert_eq!(report.restless_minutes, 0);
+    }
+}
@@ -0,0 +1,162 @@
+use crate::types::MotionState;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::time::{Duration, Instant};
+
+/// 寝返りなどを検知して早めに起こす設定
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SmartWakeSettings {
+    pub enabled: bool,                 // スマート起床を有効にするかどうか
+    pub window_minutes: u32,           // アラーム時刻の何分前から検知するか
+    pub velocity_threshold: f32,       // VelocityMagnitudeのしきい値
+    pub angular_threshold: f32,        // AngularYの絶対値のしきい値
+    pub upright_change_threshold: f32, // 検知開始時からのUprightの変化量のしきい値
+}
+
+impl Default for SmartWakeSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            window_minutes: 30,
+            velocity_threshold: 0.3,
+            angular_threshold: 0.5,
+            upright_change_threshold: 0.3,
+        }
+    }
+}
+
+impl SmartWakeSettings {
+    // 各値を有効範囲に丸め込み
+    pub fn clamped(self) -> Self {
+        Self {
+            window_minutes: self.window_minutes.clamp(1, 120),
+            velocity_threshold: self.velocity_threshold.clamp(0.0, 10.0),
+            angular_threshold: self.angular_threshold.clamp(0.0, 10.0),
+            upright_change_threshold: self.upright_change_threshold.clamp(0.0, 1.0),
+            ..self
+        }
+    }
+
+    // 検知を開始する時刻（アラーム時刻の指定分前）
+    pub fn window_start(&self, fire_at: Instant) -> Instant {
+        let window = Duration::from_secs(self.window_minutes as u64 * 60);
+        fire_at.checked_sub(window).unwrap_or(fire_at)
+    }
+
+    // 動きの情報から目覚めの兆候を検知（検知開始より前に受信した動きは使わない）
+    pub fn detect_stirring(
+        &self,
+        motion: &MotionState,
+        baseline_upright: f32,
+        watch_started_at: DateTime<Utc>,
+    ) -> Option<WakeTrigger> {
+        let is_fresh = motion
+            .last_update
+            .is_some_and(|updated_at| updated_at >= watch_started_at);
+        if !is_fresh {
+            None
+        } else if motion.velocity_magnitude >= self.velocity_threshold {
+            Some(WakeTrigger::Velocity)
+        } else if motion.angular_y.abs() >= self.angular_threshold {
+            Some(WakeTrigger::Rotation)
+        } else if (motion.upright - baseline_upright).abs() >= self.upright_change_threshold {
+            Some(WakeTrigger::Upright)
+        } else {
+            None
+        }
+    }
+}
+
+/// アラームが発火したきっかけ
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WakeTrigger {
+    Scheduled, // 設定時刻に到達
+    Velocity,  // 移動を検知
+    Rotation,  // 回転（寝返り）を検知
+    Upright,   // 姿勢の変化を検知
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn watch_started_at() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 10, 19, 6, 30, 0).unwrap()
+    }
+
+    // 検知開始の1秒後に受信した、動きのない状態
+    fn still_motion() -> MotionState {
+        MotionState {
+            upright: 0.2,
+            last_update: Some(watch_started_at() + chrono::Duration::seconds(1)),
+            ..MotionState::default()
+        }
+    }
+
+    #[test]
+    fn triggers_at_each_threshold() {
+        let settings = SmartWakeSettings::default();
+        let detect =
+            |motion: MotionState| settings.detect_stirring(&motion, 0.2, watch_started_at());
+        assert_eq!(detect(still_motion()), None);
+        // しきい値ちょうどで検知し、わずかに下回る場合は検知しない
+        let velocity = |velocity_magnitude| MotionState {
+            velocity_magnitude,
+            ..still_motion()
+        };
+        assert_eq!(detect(velocity(0.3)), Some(WakeTrigger::Velocity));
+        assert_eq!(detect(velocity(0.29)), None);
+        let rotation = |angular_y| MotionState {
+            angular_y,
+            ..still_motion()
+        };
+        assert_eq!(detect(rotation(-0.5)), Some(WakeTrigger::Rotation));
+        assert_eq!(detect(rotation(0.49)), None);
+        let upright = |upright| MotionState {
+            upright,
+            ..still_motion()
+        };
+        assert_eq!(detect(upright(0.5)), Some(WakeTrigger::Upright));
+        assert_eq!(detect(upright(0.0)), None);
+    }
+
+    #[test]
+    fn ignores_motion_received_before_the_window() {
+        let settings = SmartWakeSettings::default();
+        let moving = |last_update| MotionState {
+            velocity_magnitude: 1.0,
+            last_update,
+            ..still_motion()
+        };
+        let started_at = watch_started_at();
+        assert_eq!(
+            settings.detect_stirring(&moving(None), 0.2, started_at),
+            None
+        );
+        let before = Some(started_at - chrono::Duration::milliseconds(1));
+        assert_eq!(
+            settings.detect_stirring(&moving(before), 0.2, started_at),
+            None
+        );
+        assert_eq!(
+            settings.detect_stirring(&moving(Some(started_at)), 0.2, started_at),
+            Some(WakeTrigger::Velocity)
+        );
+    }
+
+    #[test]
+    fn window_starts_before_the_alarm() {
+        let settings = SmartWakeSettings {
+            window_minutes: 30,
+            ..SmartWakeSettings::default()
+        };
+        let fire_at = Instant::now() + Duration::from_secs(3600);
+        assert_eq!(
+            settings.window_start(fire_at),
+            fire_at - Duration::from_secs(30 * 60)
+        );
+    }
+}
@@ -0,0 +1,196 @@
+use crate::utils::clamp_snooze_duration_minutes;
+use serde::{Deserialize, Serialize};
+
+/// スヌーズ間隔の決め方
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SnoozeStrategy {
+    /// 毎回 `snooze_duration_minutes` だけ待機する
+    #[default]
+    Fixed,
+    /// 開始値から1回ごとに短くする（例: 9, 7, 5...）
+    Decreasing {
+        start_minutes: u32,
+        step_minutes: u32,
+        min_minutes: u32,
+    },
+    /// スヌーズ回ごとの間隔を指定する（リストを超えた分は最後の値を繰り返す）
+    List { minutes: Vec<u32> },
+    /// 指定範囲内でランダムに決める
+    Randomized { min_minutes: u32, max_minutes: u32 },
+}
+
+impl SnoozeStrategy {
+    /// 各値を `save_timer_settings` と同じ範囲に丸め込み
+    pub fn clamped(self) -> Self {
+        match self {
+            SnoozeStrategy::Fixed => SnoozeStrategy::Fixed,
+            SnoozeStrategy::Decreasing {
+                start_minutes,
+                step_minutes,
+                min_minutes,
+            } => SnoozeStrategy::Decreasing {
+                start_minutes: clamp_snooze_duration_minutes(start_minutes),
+                // 短くする量は0（毎回同じ間隔）も許可する
+                step_minutes: step_minutes.min(clamp_snooze_duration_minutes(step_minutes)),
+                min_minutes: clamp_snooze_duration_minutes(min_minutes),
+            },
+            SnoozeStrategy::List { minutes } => SnoozeStrategy::List {
+                minutes: minutes
+                    .into_iter()
+                    .map(clamp_snooze_duration_minutes)
+                    .collect(),
+            },
+            SnoozeStrategy::Randomized {
+                min_minutes,
+                max_minutes,
+            } => SnoozeStrategy::Randomized {
+                min_minutes: clamp_snooze_duration_minutes(min_minutes),
+                max_minutes: clamp_snooze_duration_minutes(max_minutes),
+            },
+        }
+    }
+
+    /// 設定値を検証
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            SnoozeStrategy::Fixed => Ok(()),
+            SnoozeStrategy::Decreasing {
+                start_minutes,
+                min_minutes,
+                ..
+            } => {
+                if min_minutes > start_minutes {
+                    return Err(format!(
+                        "Minimum snooze ({} min) must not exceed the starting snooze ({} min)",
+                        min_minutes, start_minutes
+                    ));
+                }
+                Ok(())
+            }
+            SnoozeStrategy::List { minutes } => {
+                if minutes.is_empty() {
+                    return Err("Snooze list must contain at least one interval".to_string());
+                }
+                Ok(())
+            }
+            SnoozeStrategy::Randomized {
+                min_minutes,
+                max_minutes,
+            } => {
+                if min_minutes > max_minutes {
+                    return Err(format!(
+                        "Minimum snooze ({} min) must not exceed the maximum snooze ({} min)",
+                        min_minutes, max_minutes
+                    ));
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// `snooze_number` 回目（1始まり）のスヌーズの待機分数を計算
+    ///
+    /// `random` は `Randomized` の場合のみ使用する乱数値。結果は `save_timer_settings` と同じ範囲に丸め込む。
+    pub fn snooze_minutes(&self, snooze_number: u32, fixed_minutes: u32, random: u64) -> u32 {
+        let index = snooze_number.saturating_sub(1);
+        let minutes = match self {
+            SnoozeStrategy::Fixed => fixed_minutes,
+            SnoozeStrategy::Decreasing {
+                start_minutes,
+                step_minutes,
+                min_minutes,
+            } => start_minutes
+                .saturating_sub(step_minutes.saturating_mul(index))
+                .max(*min_minutes),
+            SnoozeStrategy::List { minutes } => minutes
+                .get(index as usize)
+                .or(minutes.last())
+                .copied()
+                .unwrap_or(fixed_minutes),
+            SnoozeStrategy::Randomized {
+                min_minutes,
+                max_minutes,
+            } => {
+                let (low, high) = (*min_minutes.min(max_minutes), *min_minutes.max(max_minutes));
+                // u32::MAXまでの範囲でも桁あふれしないようu64で計算
+                let span = high as u64 - low as u64 + 1;
+                (low as u64 + random % span) as u32
+            }
+        };
+        clamp_snooze_duration_minutes(minutes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixed_uses_configured_duration() {
+        let strategy = SnoozeStrategy::Fixed;
+        assert_eq!(strategy.snooze_minutes(1, 9, 0), 9);
+        assert_eq!(strategy.snooze_minutes(5, 9, 0), 9);
+        assert_eq!(strategy.snooze_minutes(1, 90, 0), 30);
+    }
+
+    #[test]
+    fn randomized_full_range_does_not_overflow() {
+        let strategy = SnoozeStrategy::Randomized {
+            min_minutes: 0,
+            max_minutes: u32::MAX,
+        };
+        assert!(strategy.validate().is_ok());
+        for random in [0, 1, u64::MAX] {
+            assert!((1..=30).contains(&strategy.snooze_minutes(1, 9, random)));
+        }
+        assert_eq!(
+            strategy.clamped(),
+            SnoozeStrategy::Randomized {
+                min_minutes: 1,
+                max_minutes: 30,
+            }
+        );
+    }
+
+    #[test]
+    fn decreasing_shortens_until_minimum() {
+        let strategy = SnoozeStrategy::Decreasing {
+            start_minutes: 9,
+            step_minutes: 2,
+            min_minutes: 3,
+        };
+        let minutes: Vec<u32> = (1..=5).map(|n| strategy.snooze_minutes(n, 9, 0)).collect();
+        assert_eq!(minutes, vec![9, 7, 5, 3, 3]);
+    }
+
+    #[test]
+    fn list_repeats_last_interval() {
+        let strategy = SnoozeStrategy::List {
+            minutes: vec![10, 5, 2],
+        };
+        let minutes: Vec<u32> = (1..=5).map(|n| strategy.snooze_minutes(n, 9, 0)).collect();
+        assert_eq!(minutes, vec![10, 5, 2, 2, 2]);
+        assert!(SnoozeStrategy::List { minutes: vec![] }.validate().is_err());
+    }
+
+    #[test]
+    fn randomized_stays_within_bounds() {
+        let strategy = SnoozeStrategy::Randomized {
+            min_minutes: 4,
+            max_minutes: 8,
+        };
+        for random in 0..100 {
+            let minutes = strategy.snooze_minutes(1, 9, random);
+            assert!((4..=8).contains(&minutes));
+        }
+        assert_eq!(strategy.snooze_minutes(1, 9, 0), 4);
+        assert_eq!(strategy.snooze_minutes(1, 9, 4), 8);
+        assert!(SnoozeStrategy::Randomized {
+            min_minutes: 8,
+            max_minutes: 4
+        }
+        .validate()
+        .is_err());
+    }
+}
@@ -0,0 +1,203 @@
+use chrono::{DateTime, Datelike, Local, NaiveDate, NaiveTime, TimeZone, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+
+// 日の出の太陽高度（大気差と太陽の視半径を考慮した値、度）
+const SUNRISE_ZENITH_DEGREES: f64 = 90.833;
+// 日の出時刻の計算を繰り返す回数（時刻に合わせて太陽の位置を再計算する）
+const SUNRISE_ITERATIONS: usize = 2;
+
+/// 日の出を基準にしたアラームの設定
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SolarAlarmSettings {
+    pub latitude: f64,        // 緯度（北緯が正）
+    pub longitude: f64,       // 経度（東経が正）
+    pub offset_minutes: i32,  // 日の出からのずれ（負の場合は日の出前）
+    pub earliest_hour: u32,   // これより早くは鳴らさない時刻（時）
+    pub earliest_minute: u32, // これより早くは鳴らさない時刻（分）
+    pub latest_hour: u32,     // これより遅くは鳴らさない時刻（時）
+    pub latest_minute: u32,   // これより遅くは鳴らさない時刻（分）
+}
+
+impl Default for SolarAlarmSettings {
+    fn default() -> Self {
+        // 東京
+        Self {
+            latitude: 35.6895,
+            longitude: 139.6917,
+            offset_minutes: 0,
+            earliest_hour: 5,
+            earliest_minute: 0,
+            latest_hour: 8,
+            latest_minute: 0,
+        }
+    }
+}
+
+impl SolarAlarmSettings {
+    // 各値を有効範囲に丸め込み
+    pub fn clamped(self) -> Self {
+        Self {
+            latitude: self.latitude.clamp(-90.0, 90.0),
+            longitude: self.longitude.clamp(-180.0, 180.0),
+            offset_minutes: self.offset_minutes.clamp(-180, 180),
+            earliest_hour: self.earliest_hour.min(23),
+            earliest_minute: self.earliest_minute.min(59),
+            latest_hour: self.latest_hour.min(23),
+            latest_minute: self.latest_minute.min(59),
+        }
+    }
+
+    // 設定値を検証
+    pub fn validate(&self) -> Result<(), String> {
+        if !(-90.0..=90.0).contains(&self.latitude) {
+            return Err(format!("Latitude {} is out of range", self.latitude));
+        }
+        if !(-180.0..=180.0).contains(&self.longitude) {
+            return Err(format!("Longitude {} is out of range", self.longitude));
+        }
+        if (self.earliest_hour, self.earliest_minute) > (self.latest_hour, self.latest_minute) {
+            return Err(format!(
+                "Earliest time ({:02}:{:02}) must not be later than the latest time ({:02}:{:02})",
+                self.earliest_hour, self.earliest_minute, self.latest_hour, self.latest_minute
+            ));
+        }
+        Ok(())
+    }
+
+    // 指定した日のアラーム時刻（日の出＋ずれを時間帯に収めた時刻）を計算
+    // 白夜・極夜で日の出がない場合はNone
+    pub fn alarm_time_on(&self, date: NaiveDate) -> Option<DateTime<Local>> {
+        let sunrise = sunrise_utc(date, self.latitude, self.longitude)?.with_timezone(&Local);
+        let target = sunrise + chrono::Duration::minutes(self.offset_minutes as i64);
+
+        let earliest = NaiveTime::from_hms_opt(self.earliest_hour, self.earliest_minute, 0)?;
+        let latest = NaiveTime::from_hms_opt(self.latest_hour, self.latest_minute, 0)?;
+        // アラームは分単位で鳴らすため、秒以下を切り捨てる
+        let time = NaiveTime::from_hms_opt(target.hour(), target.minute(), 0)?
+            .clamp(earliest, latest.max(earliest));
+        Local.from_local_datetime(&date.and_time(time)).earliest()
+    }
+}
+
+// NOAAの太陽位置の計算式を用いて、指定した日の日の出時刻（UTC）を計算
+pub fn sunrise_utc(date: NaiveDate, latitude: f64, longitude: f64) -> Option<DateTime<Utc>> {
+    let midnight = Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0)?);
+    // 0時UTCのユリウス日
+    let julian_day = date.num_days_from_ce() as f64 + 1721424.5;
+
+    // 最初は南中時刻付近で太陽の位置を求め、求めた日の出時刻で再計算する
+    let mut minutes = 720.0;
+    for _ in 0..SUNRISE_ITERATIONS {
+        let (declination, equation_of_time) = solar_position(julian_day + minutes / 1440.0);
+        let hour_angle = sunrise_hour_angle(latitude, declination)?;
+        minutes = 720.0 - 4.0 * (longitude + hour_angle) - equation_of_time;
+    }
+
+    Some(midnight + chrono::Duration::seconds((minutes * 60.0).round() as i64))
+}
+
+// 太陽の赤緯（度）と均時差（分）を計算
+fn solar_position(julian_day: f64) -> (f64, f64) {
+    let t = (julian_day - 2451545.0) / 36525.0;
+
+    let mean_longitude = (280.46646 + t * (36000.76983 + t * 0.0003032)).rem_euclid(360.0);
+    let mean_anomaly = 357.52911 + t * (35999.05029 - 0.0001537 * t);
+    let eccentricity = 0.016708634 - t * (0.000042037 + 0.0000001267 * t);
+
+    let m = mean_anomaly.to_radians();
+    let equation_of_center = m.sin() * (1.914602 - t * (0.004817 + 0.000014 * t))
+        + (2.0 * m).sin() * (0.019993 - 0.000101 * t)
+        + (3.0 * m).sin() * 0.000289;
+    let true_longitude = mean_longitude + equation_of_center;
+
+    let omega = (125.04 - 1934.136 * t).to_radians();
+    let apparent_longitude = true_longitude - 0.00569 - 0.00478 * omega.sin();
+
+    let mean_obliquity =
+        23.0 + (26.0 + (21.448 - t * (46.815 + t * (0.00059 - t * 0.001813))) / 60.0) / 60.0;
+    let obliquity = (mean_obliquity + 0.00256 * omega.cos()).to_radians();
+
+    let declination = (obliquity.sin() * apparent_longitude.to_radians().sin()).asin();
+
+    let y = (obliquity / 2.0).tan().powi(2);
+    let l0 = mean_longitude.to_radians();
+    let equation_of_time = 4.0
+        * (y * (2.0 * l0).sin() - 2.0 * eccentricity * m.sin()
+            + 4.0 * eccentricity * y * m.sin() * (2.0 * l0).cos()
+            - 0.5 * y * y * (4.0 * l0).sin()
+            - 1.25 * eccentricity * eccentricity * (2.0 * m).sin())
+        .to_degrees();
+
+    (declination.to_degrees(), equation_of_time)
+}
+
+// 日の出の時角（度）を計算（白夜・極夜の場合はNone）
+fn sunrise_hour_angle(latitude: f64, declination: f64) -> Option<f64> {
+    let lat = latitude.to_radians();
+    let decl = declination.to_radians();
+    let cos_hour_angle = SUNRISE_ZENITH_DEGREES.to_radians().cos() / (lat.cos() * decl.cos())
+        - lat.tan() * decl.tan();
+    if !(-1.0..=1.0).contains(&cos_hour_angle) {
+        return None;
+    }
+    Some(cos_hour_angle.acos().to_degrees())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::FixedOffset;
+
+    const TOKYO: (f64, f64) = (35.6895, 139.6917);
+
+    // 指定したタイムゾーンでの日の出時刻を分単位で取得
+    fn local_sunrise_minutes(date: NaiveDate, (lat, lon): (f64, f64), offset_hours: i32) -> i64 {
+        let offset = FixedOffset::east_opt(offset_hours * 3600).unwrap();
+        let sunrise = sunrise_utc(date, lat, lon).unwrap().with_timezone(&offset);
+        assert_eq!(sunrise.date_naive(), date);
+        sunrise.hour() as i64 * 60 + sunrise.minute() as i64
+    }
+
+    fn assert_close(actual: i64, hour: i64, minute: i64) {
+        let expected = hour * 60 + minute;
+        assert!(
+            (actual - expected).abs() <= 2,
+            "expected {:02}:{:02}, got {:02}:{:02}",
+            hour,
+            minute,
+            actual / 60,
+            actual % 60
+        );
+    }
+
+    #[test]
+    fn tokyo_summer_solstice() {
+        let date = NaiveDate::from_ymd_opt(2024, 6, 21).unwrap();
+        assert_close(local_sunrise_minutes(date, TOKYO, 9), 4, 25);
+    }
+
+    #[test]
+    fn tokyo_winter_solstice() {
+        let date = NaiveDate::from_ymd_opt(2024, 12, 21).unwrap();
+        assert_close(local_sunrise_minutes(date, TOKYO, 9), 6, 47);
+    }
+
+    #[test]
+    fn tokyo_spring_equinox() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+        assert_close(local_sunrise_minutes(date, TOKYO, 9), 5, 45);
+    }
+
+    #[test]
+    fn london_summer_solstice() {
+        let date = NaiveDate::from_ymd_opt(2024, 6, 21).unwrap();
+        assert_close(local_sunrise_minutes(date, (51.5074, -0.1278), 1), 4, 43);
+    }
+
+    #[test]
+    fn polar_night_has_no_sunrise() {
+        let date = NaiveDate::from_ymd_opt(2024, 12, 21).unwrap();
+        assert!(sunrise_utc(date, 69.6492, 18.9553).is_none());
+    }
+}
@@ -0,0 +1,248 @@
+use crate::osc::send_osc_bundle_to_vrchat;
+use crate::types::{AppStateMutex, TimerManagerMutex};
+use chrono::{DateTime, Utc};
+use rosc::{OscMessage, OscType};
+use serde::{Deserialize, Serialize};
+use tokio::time::{sleep, Duration};
+
+// 経過時間パラメータを送信する間隔
+const STOPWATCH_PUBLISH_INTERVAL: Duration = Duration::from_secs(1);
+// 保持するラップの最大数
+const STOPWATCH_MAX_LAPS: usize = 99;
+// Intパラメータで表せる最大値（アバターのInt型は0〜255）
+const AVATAR_INT_MAX: i64 = 255;
+
+/// ストップウォッチの操作
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StopwatchAction {
+    Start,
+    Stop,
+    Lap,
+    Reset,
+}
+
+impl StopwatchAction {
+    /// アバターパラメータのアドレスから取得
+    pub fn from_address(addr: &str) -> Option<Self> {
+        match addr {
+            "/avatar/parameters/StopwatchStart" => Some(Self::Start),
+            "/avatar/parameters/StopwatchStop" => Some(Self::Stop),
+            "/avatar/parameters/StopwatchLap" => Some(Self::Lap),
+            "/avatar/parameters/StopwatchReset" => Some(Self::Reset),
+            _ => None,
+        }
+    }
+}
+
+/// ラップの記録
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StopwatchLap {
+    pub number: u32,                // ラップ番号（1から）
+    pub lap_ms: i64,                // 前回のラップからの経過時間
+    pub total_ms: i64,              // 開始からの経過時間
+    pub recorded_at: DateTime<Utc>, // 記録した時刻
+}
+
+/// ストップウォッチの状態
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Stopwatch {
+    pub running_since: Option<DateTime<Utc>>, // 計測中の場合は現在の区間の開始時刻
+    pub accumulated_ms: i64,                  // 停止までに計測した時間の合計
+    pub laps: Vec<StopwatchLap>,              // ラップの履歴
+}
+
+impl Stopwatch {
+    pub fn is_running(&self) -> bool {
+        self.running_since.is_some()
+    }
+
+    // 開始からの経過時間（ミリ秒）
+    pub fn elapsed_ms(&self, now: DateTime<Utc>) -> i64 {
+        let running_ms = self
+            .running_since
+            .map(|since| now.signed_duration_since(since).num_milliseconds().max(0))
+            .unwrap_or(0);
+        self.accumulated_ms + running_ms
+    }
+
+    // 経過時間をアバターのIntパラメータで表せる時・分・秒に分割（255時間以上は255時間にする）
+    pub fn elapsed_parts(&self, now: DateTime<Utc>) -> (i32, i32, i32) {
+        let elapsed_seconds = self.elapsed_ms(now) / 1000;
+        let hours = elapsed_seconds / 3600;
+        if hours > AVATAR_INT_MAX {
+            return (AVATAR_INT_MAX as i32, 59, 59);
+        }
+        (
+            hours as i32,
+            (elapsed_seconds / 60 % 60) as i32,
+            (elapsed_seconds % 60) as i32,
+        )
+    }
+
+    // 操作を適用し、状態が変化したかどうかを返す
+    fn apply(&mut self, action: StopwatchAction, now: DateTime<Utc>) -> bool {
+        match action {
+            StopwatchAction::Start => {
+                if self.is_running() {
+                    return false;
+                }
+                self.running_since = Some(now);
+            }
+            StopwatchAction::Stop => {
+                if !self.is_running() {
+                    return false;
+                }
+                self.accumulated_ms = self.elapsed_ms(now);
+                self.running_since = None;
+            }
+            StopwatchAction::Lap => {
+                if !self.is_running() || self.laps.len() >= STOPWATCH_MAX_LAPS {
+                    return false;
+                }
+                let total_ms = self.elapsed_ms(now);
+                let previous_ms = self.laps.last().map(|lap| lap.total_ms).unwrap_or(0);
+                self.laps.push(StopwatchLap {
+                    number: self.laps.len() as u32 + 1,
+                    lap_ms: total_ms - previous_ms,
+                    total_ms,
+                    recorded_at: now,
+                });
+            }
+            StopwatchAction::Reset => {
+                *self = Stopwatch::default();
+            }
+        }
+        true
+    }
+}
+
+// ストップウォッチを操作し、経過時間の送信を開始・停止する
+pub async fn apply_stopwatch_action(
+    state: AppStateMutex,
+    timer_manager: TimerManagerMutex,
+    action: StopwatchAction,
+) {
+    let (changed, running) = match state.lock() {
+        Ok(mut app_state) => {
+            let changed = app_state.stopwatch.apply(action, Utc::now());
+            (changed, app_state.stopwatch.is_running())
+        }
+        Err(e) => {
+            eprintln!("Failed to lock state: {}", e);
+            return;
+        }
+    };
+    if !changed {
+        return;
+    }
+    println!("Stopwatch: {:?}", action);
+
+    // 計測中は定期的に送信し、停止・リセット時は最終値を送信
+    if let Ok(mut timer_mgr) = timer_manager.lock() {
+        if action == StopwatchAction::Start {
+            let state_clone = state.clone();
+            timer_mgr.set_stopwatch_timer(tokio::spawn(async move {
+                loop {
+                    publish_stopwatch(&state_clone).await;
+                    sleep(STOPWATCH_PUBLISH_INTERVAL).await;
+                }
+            }));
+        } else if !running {
+            timer_mgr.cancel_stopwatch_timer();
+        }
+    }
+    publish_stopwatch(&state).await;
+}
+
+// 経過時間（時・分・秒）とラップ数をVRChatに送信
+async fn publish_stopwatch(state: &AppStateMutex) {
+    let ((hours, minutes, seconds), running, lap_count) = match state.lock() {
+        Ok(app_state) => (
+            app_state.stopwatch.elapsed_parts(Utc::now()),
+            app_state.stopwatch.is_running(),
+            app_state.stopwatch.laps.len(),
+        ),
+        Err(e) => {
+            eprintln!("Failed to lock state: {}", e);
+            return;
+        }
+    };
+
+    let messages = vec![
+        OscMessage {
+            addr: "/avatar/parameters/StopwatchHours".to_string(),
+            args: vec![OscType::Int(hours)],
+        },
+        OscMessage {
+            addr: "/avatar/parameters/StopwatchMinutes".to_string(),
+            args: vec![OscType::Int(minutes)],
+        },
+        OscMessage {
+            addr: "/avatar/parameters/StopwatchSeconds".to_string(),
+            args: vec![OscType::Int(seconds)],
+        },
+        OscMessage {
+            addr: "/avatar/parameters/StopwatchRunning".to_string(),
+            args: vec![OscType::Bool(running)],
+        },
+        OscMessage {
+            addr: "/avatar/parameters/StopwatchLapCount".to_string(),
+            args: vec![OscType::Int(lap_count as i32)],
+        },
+    ];
+    if let Err(e) = send_osc_bundle_to_vrchat(messages, state).await {
+        eprintln!("Failed to send stopwatch parameters: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(seconds: i64) -> DateTime<Utc> {
+        Utc.timestamp_opt(1_800_000_000 + seconds, 0).unwrap()
+    }
+
+    #[test]
+    fn start_stop_lap_and_reset() {
+        let mut stopwatch = Stopwatch::default();
+        // 停止中のラップと停止は無視する
+        assert!(!stopwatch.apply(StopwatchAction::Lap, at(0)));
+        assert!(!stopwatch.apply(StopwatchAction::Stop, at(0)));
+
+        assert!(stopwatch.apply(StopwatchAction::Start, at(0)));
+        assert!(!stopwatch.apply(StopwatchAction::Start, at(5)));
+        assert!(stopwatch.apply(StopwatchAction::Lap, at(30)));
+        assert!(stopwatch.apply(StopwatchAction::Stop, at(50)));
+        // 停止中の時間は含めない
+        assert!(stopwatch.apply(StopwatchAction::Start, at(100)));
+        assert!(stopwatch.apply(StopwatchAction::Lap, at(120)));
+        let laps: Vec<(u32, i64, i64)> = stopwatch
+            .laps
+            .iter()
+            .map(|lap| (lap.number, lap.lap_ms, lap.total_ms))
+            .collect();
+        assert_eq!(laps, vec![(1, 30_000, 30_000), (2, 40_000, 70_000)]);
+        assert_eq!(stopwatch.elapsed_ms(at(130)), 80_000);
+
+        assert!(stopwatch.apply(StopwatchAction::Reset, at(140)));
+        assert!(!stopwatch.is_running());
+        assert!(stopwatch.laps.is_empty());
+        assert_eq!(stopwatch.elapsed_ms(at(150)), 0);
+    }
+
+    #[test]
+    fn elapsed_parts_fit_in_avatar_ints() {
+        let stopwatch = Stopwatch {
+            running_since: Some(at(0)),
+            ..Stopwatch::default()
+        };
+        // 255分を超えても分は0〜59の範囲
+        assert_eq!(
+            stopwatch.elapsed_parts(at(4 * 3600 + 16 * 60 + 5)),
+            (4, 16, 5)
+        );
+        assert_eq!(stopwatch.elapsed_parts(at(300 * 3600)), (255, 59, 59));
+    }
+}
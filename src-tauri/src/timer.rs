@@ -1,11 +1,32 @@
-use crate::osc::send_osc_to_vrchat;
-use crate::types::{AppStateMutex, TimerEvent, TimerManagerMutex};
-use crate::utils::{vrc_float_to_hour, vrc_float_to_minute};
-use chrono::{Local, Timelike};
-use rosc::OscType;
+use crate::bedtime::remind_bedtime;
+use crate::chatbox::{notify_chatbox, ChatboxEvent};
+use crate::config::{load_settings, save_settings};
+use crate::dismiss::{clear_dismissal, send_challenge, Challenge, MovementDismissMode};
+use crate::escalation::EscalationSettings;
+use crate::holiday::{HolidayAction, HolidayCalendar};
+use crate::osc::{send_osc_bundle_to_vrchat, send_osc_to_vrchat};
+use crate::quiet::QuietDecision;
+use crate::schedule::{local_time_on, next_alarm_time, next_skipped_date};
+use crate::skip::{send_skip_state, SkipSettings};
+use crate::sleep::{finish_session, spawn_save_finished_session};
+use crate::smart_wake::{SmartWakeSettings, WakeTrigger};
+use crate::types::{
+    AlarmSettings, AlarmSource, AlarmType, AppStateMutex, EventKind, NextFireInfo, RampCurve,
+    TimerEvent, TimerManagerMutex,
+};
+use crate::utils::{random_u64, vrc_float_to_hour, vrc_float_to_minute};
+use chrono::{Local, Utc};
+use rosc::{OscMessage, OscType};
 use std::future::Future;
 use std::pin::Pin;
-use tokio::time::{sleep, Duration};
+use tokio::time::{sleep, sleep_until, Duration, Instant};
+
+// 鳴動中に進捗パラメータを送信する間隔
+const RINGING_PROGRESS_INTERVAL: Duration = Duration::from_secs(5);
+// サンライズ中に明るさパラメータを送信する間隔
+const SUNRISE_UPDATE_INTERVAL: Duration = Duration::from_secs(10);
+// スマート起床の検知間隔
+const SMART_WAKE_POLL_INTERVAL: Duration = Duration::from_secs(2);
 
 // 次のアラームの時刻を計算し、タイマーを設定する
 pub fn calculate_and_set_next_alarm(
@@ -26,23 +47,36 @@ pub fn calculate_and_set_next_alarm(
         }
 
         // アラームの設定を取得
-        let (alarm_on, alarm_hour, alarm_minute) = {
-            let app_state = match state.lock() {
+        let (alarm_on, alarm_hour, alarm_minute, sunrise_active) = {
+            let mut app_state = match state.lock() {
                 Ok(state) => state,
                 Err(e) => {
                     eprintln!("Failed to lock state: {}", e);
                     return;
                 }
             };
+            // キャンセルしたスヌーズの再発火時刻を破棄
+            app_state.snooze_until = None;
             (
                 app_state.alarm_is_on,
                 app_state.alarm_set_hour,
                 app_state.alarm_set_minute,
+                app_state.sunrise_level > 0.0 && !app_state.is_ringing,
             )
         };
 
+        // 途中のサンライズがあれば明るさをリセット
+        if sunrise_active {
+            send_sunrise_level(&state, 0.0).await;
+        }
+
         // アラームがオフの場合は何もしない
         if !alarm_on {
+            if let Ok(mut app_state) = state.lock() {
+                app_state.next_alarm_at = None;
+                app_state.bedtime_at = None;
+                app_state.next_skipped_date = None;
+            }
             println!("Alarm is OFF, no timer set");
             return;
         }
@@ -59,23 +93,81 @@ pub fn calculate_and_set_next_alarm(
         let alarm_hour = vrc_float_to_hour(alarm_hour) as u32;
         let alarm_minute = vrc_float_to_minute(alarm_minute) as u32;
 
-        // アラームの目標時刻を作成（秒とナノ秒は0に設定）
-        let mut target_time = now
-            .with_hour(alarm_hour)
-            .and_then(|t| t.with_minute(alarm_minute))
-            .and_then(|t| t.with_second(0))
-            .and_then(|t| t.with_nanosecond(0))
-            .unwrap();
-
-        // 目標時刻が現在時刻より過去の場合は翔日に設定
-        if now >= target_time {
-            target_time += chrono::Duration::days(1);
+        // アラームの目標時刻を作成（現在時刻より後で、曜日の指定に一致する日）
+        let settings = load_settings();
+        let skip = expire_skips(&state, &settings, now).await;
+        let solar_alarm = settings.solar_alarm.clone().clamped();
+        let cron_schedule = match settings.alarm_type {
+            AlarmType::Cron => match settings.cron_alarm.clone().clamped().schedule() {
+                Ok(schedule) => Some(schedule),
+                Err(e) => {
+                    eprintln!("Invalid cron expression: {}", e);
+                    None
+                }
+            },
+            _ => None,
+        };
+        let base_alarm_at = |date| match settings.alarm_type {
+            AlarmType::Fixed => local_time_on(date, alarm_hour, alarm_minute),
+            // 日の出がない日（白夜・極夜）は設定した時刻に鳴らす
+            AlarmType::Sunrise => solar_alarm
+                .alarm_time_on(date)
+                .or_else(|| local_time_on(date, alarm_hour, alarm_minute)),
+            // 1日に複数回一致する場合は現在時刻より後の最初の時刻（スキップした時刻は除く）
+            AlarmType::Cron => cron_schedule
+                .as_ref()
+                .and_then(|schedule| schedule.first_unskipped_on(date, now, skip.skip_at)),
+        };
+        // 祝日はスキップするか、設定した時刻に鳴らす
+        let holiday = settings.holiday.clone().clamped();
+        let holidays = holiday.enabled.then(|| HolidayCalendar::load(&holiday));
+        let is_holiday = |date| holidays.as_ref().is_some_and(|cal| cal.is_holiday(date));
+        // スキップした日と休暇中は鳴らさない
+        let alarm_at = |date| match (skip.is_skipped(date), is_holiday(date), holiday.action) {
+            (true, _, _) | (false, true, HolidayAction::Skip) => None,
+            (false, false, _) => base_alarm_at(date),
+            (false, true, HolidayAction::Shift) => {
+                local_time_on(date, holiday.shift_hour, holiday.shift_minute)
+            }
+        };
+        let is_skipped = |date| {
+            skip.is_skipped(date) || (is_holiday(date) && holiday.action == HolidayAction::Skip)
+        };
+        // cron式のアラームは曜日をcron式で指定するため、曜日の設定を使わない
+        let weekdays: &[u32] = match settings.alarm_type {
+            AlarmType::Cron => &[],
+            _ => &settings.alarm_weekdays,
+        };
+        let skipped_date = next_skipped_date(now, weekdays, base_alarm_at, is_skipped);
+        if let Ok(mut app_state) = state.lock() {
+            app_state.next_skipped_date = skipped_date;
         }
+        let target_time = match next_alarm_time(now, weekdays, alarm_at) {
+            Some(target_time) => target_time,
+            None => {
+                if let Ok(mut app_state) = state.lock() {
+                    app_state.next_alarm_at = None;
+                    app_state.bedtime_at = None;
+                }
+                println!("No matching day for the alarm, no timer set");
+                return;
+            }
+        };
 
         // アラームまでの待機時間を計算
         let wait_duration = target_time.signed_duration_since(now);
         let wait_std_duration = Duration::from_millis(wait_duration.num_milliseconds() as u64);
 
+        // 起床時刻と目標の睡眠時間から就寝時刻を計算
+        let bedtime = settings.bedtime.clamped();
+        let bedtime_at = bedtime.enabled.then(|| bedtime.bedtime_for(target_time));
+
+        // 次のアラーム時刻を記録
+        if let Ok(mut app_state) = state.lock() {
+            app_state.next_alarm_at = Some(target_time.with_timezone(&Utc));
+            app_state.bedtime_at = bedtime_at.map(|at| at.with_timezone(&Utc));
+        }
+
         // 次のアラーム時刻をログ出力
         println!(
             "Next alarm set for: {} (in {} minutes)",
@@ -83,16 +175,119 @@ pub fn calculate_and_set_next_alarm(
             wait_duration.num_minutes()
         );
 
+        // 就寝リマインダーの待機時間（既に過ぎている場合は省略）
+        let bedtime_reminder = bedtime_at.and_then(|bedtime_at| {
+            let reminder_wait = bedtime
+                .reminder_for(bedtime_at)
+                .signed_duration_since(now)
+                .to_std()
+                .ok()?;
+            Some((reminder_wait, bedtime_at))
+        });
+
+        // サンライズとチャットボックス通知の設定を取得
+        let sunrise = settings.sunrise_enabled.then(|| {
+            (
+                Duration::from_secs(settings.sunrise_minutes.clamp(1, 120) as u64 * 60),
+                settings.sunrise_curve,
+            )
+        });
+        let chatbox = settings.chatbox.clamped();
+        let notice_minutes = chatbox.notify_before_minutes;
+        let notice_before = (chatbox.enabled && notice_minutes > 0)
+            .then(|| Duration::from_secs(notice_minutes as u64 * 60));
+        let smart_wake = settings
+            .smart_wake
+            .enabled
+            .then(|| settings.smart_wake.clamped());
+
         // アラーム発火用のタイマーを作成
         let state_clone = state.clone();
         let timer_manager_clone = timer_manager.clone();
 
         let timer_handle = tokio::spawn(async move {
-            // 指定した時間だけ待機
-            sleep(wait_std_duration).await;
-            // スヌーズ回数をリセット
-            if let Ok(mut app_state) = state_clone.lock() {
-                app_state.snooze_count = 0;
+            let fire_at = Instant::now() + wait_std_duration;
+            // 指定した時間だけ待機（サンライズ有効時は発火前に明るさを段階的に上げる）
+            let wait = async {
+                match sunrise {
+                    Some((window, curve)) => {
+                        run_sunrise_ramp(&state_clone, fire_at, window, curve).await
+                    }
+                    None => sleep_until(fire_at).await,
+                }
+            };
+            // 発火前にチャットボックスで予告（既に予告時刻を過ぎている場合は省略）
+            // おやすみ時間で遅れても発火を待たせないよう、送信は別のタスクで行う
+            let pre_notice = async {
+                let notice_at = notice_before
+                    .and_then(|notice| fire_at.checked_sub(notice))
+                    .filter(|notice_at| *notice_at > Instant::now());
+                if let Some(notice_at) = notice_at {
+                    sleep_until(notice_at).await;
+                    let now = Local::now();
+                    let remaining = fire_at.saturating_duration_since(Instant::now());
+                    let fire_at_local =
+                        now + chrono::Duration::from_std(remaining).unwrap_or_default();
+                    // おやすみ時間で発火時刻より後に遅れる予告は送信しない
+                    let quiet_hours = load_settings().quiet_hours.clamped();
+                    if !quiet_hours.delivers_before(now, fire_at_local) {
+                        println!("Alarm pre-notice dropped by quiet hours.");
+                        return;
+                    }
+                    let state_clone = state_clone.clone();
+                    tokio::spawn(async move {
+                        notify_chatbox(
+                            &state_clone,
+                            ChatboxEvent::AlarmSoon,
+                            &[("minutes", notice_minutes.to_string())],
+                        )
+                        .await;
+                    });
+                }
+            };
+            // 就寝時刻の前にリマインダーを送信
+            let bedtime_notice = async {
+                if let Some((reminder_wait, bedtime_at)) = bedtime_reminder {
+                    sleep(reminder_wait).await;
+                    remind_bedtime(&state_clone, &bedtime, bedtime_at).await;
+                }
+            };
+            // スマート起床が有効な場合は、時刻より前でも目覚めの兆候を検知したら発火
+            let stirring = async {
+                match smart_wake {
+                    Some(smart_wake) => {
+                        watch_for_stirring(&state_clone, fire_at, smart_wake).await
+                    }
+                    None => std::future::pending().await,
+                }
+            };
+            let trigger = tokio::select! {
+                _ = async { tokio::join!(wait, pre_notice, bedtime_notice) } => {
+                    WakeTrigger::Scheduled
+                }
+                trigger = stirring => trigger,
+            };
+            // スヌーズ回数をリセットし、発火のきっかけを記録
+            let superseded = match state_clone.lock() {
+                Ok(mut app_state) => {
+                    let busy = app_state.is_ringing || app_state.snooze_until.is_some();
+                    let previous = app_state.alarm_source;
+                    app_state.alarm_source = AlarmSource::Wake;
+                    app_state.snooze_count = 0;
+                    app_state.log_event(EventKind::AlarmFired, format!("trigger: {:?}", trigger));
+                    (busy && previous != AlarmSource::Wake).then_some(previous)
+                }
+                Err(_) => None,
+            };
+            // 他の種類のアラームが鳴動中・スヌーズ中の場合は起床アラームを優先し、そのタイマーを止める
+            if let Some(previous) = superseded {
+                println!("Wake alarm supersedes the {:?} alarm.", previous);
+                if let Ok(mut timer_mgr) = timer_manager_clone.lock() {
+                    timer_mgr.cancel_alarm_timer(previous);
+                }
+            }
+            if trigger != WakeTrigger::Scheduled {
+                println!("Smart wake: stirring detected ({:?}), firing early.", trigger);
             }
             // アラーム発火イベントを発生
             handle_timer_event(state_clone, timer_manager_clone, TimerEvent::AlarmFire).await;
@@ -104,6 +299,20 @@ pub fn calculate_and_set_next_alarm(
     })
 }
 
+// 鳴動中・スヌーズ中でなければ次のアラームを再計算する（設定変更の即時反映用）
+pub async fn reschedule_if_idle(state: AppStateMutex, timer_manager: TimerManagerMutex) {
+    let idle = match state.lock() {
+        Ok(app_state) => !app_state.is_ringing && app_state.snooze_until.is_none(),
+        Err(e) => {
+            eprintln!("Failed to lock state: {}", e);
+            return;
+        }
+    };
+    if idle {
+        calculate_and_set_next_alarm(state, timer_manager).await;
+    }
+}
+
 // アラーム関連のイベントを処理するメイン関数
 pub fn handle_timer_event(
     state: AppStateMutex,
@@ -114,6 +323,55 @@ pub fn handle_timer_event(
         match event {
             // アラーム発火時の処理
             TimerEvent::AlarmFire => {
+                let settings = load_settings();
+                let source = state
+                    .lock()
+                    .map(|app_state| app_state.alarm_source)
+                    .unwrap_or_default();
+                let presence = settings.presence.for_source(source).clone();
+                // デスクトップモードでは鳴らさない設定の場合は次のアラームを設定
+                let suppressed = presence.suppress_in_desktop
+                    && state
+                        .lock()
+                        .map(|app_state| app_state.vr_mode == Some(false))
+                        .unwrap_or(false);
+                if suppressed {
+                    println!("Alarm suppressed: desktop mode.");
+                    if let Ok(mut app_state) = state.lock() {
+                        app_state.snooze_count = 0;
+                        app_state.snooze_until = None;
+                        app_state.log_event(EventKind::AlarmSuppressed, "desktop mode");
+                    }
+                    if !source.is_chime() {
+                        calculate_and_set_next_alarm(state, timer_manager).await;
+                    }
+                    return;
+                }
+
+                // おやすみ時間中は重要なアラーム以外を遅らせるか破棄する
+                let quiet_hours = settings.quiet_hours.clamped();
+                let critical = source.is_wake() && quiet_hours.wake_alarm_critical;
+                match quiet_hours.decide(critical, Local::now()) {
+                    QuietDecision::Allow => {}
+                    QuietDecision::Defer(until) if !source.is_chime() => {
+                        println!("Alarm deferred by quiet hours until {}", until.format("%H:%M"));
+                        defer_alarm_until(state, timer_manager, source, until);
+                        return;
+                    }
+                    // 合図は遅らせても意味がないため破棄する
+                    QuietDecision::Defer(_) | QuietDecision::Drop => {
+                        println!("Alarm dropped by quiet hours.");
+                        if let Ok(mut app_state) = state.lock() {
+                            app_state.snooze_count = 0;
+                            app_state.log_event(EventKind::AlarmSuppressed, "quiet hours");
+                        }
+                        if !source.is_chime() {
+                            calculate_and_set_next_alarm(state, timer_manager).await;
+                        }
+                        return;
+                    }
+                }
+
                 println!("Alarm firing!");
                 // VRChatにアラーム発火シグナルを送信
                 if let Err(e) = send_osc_to_vrchat(
@@ -127,36 +385,112 @@ pub fn handle_timer_event(
                 }
 
                 // アラームの状態を有効にし、アラーム時間を取得
+                let escalation = settings.escalation.clamped();
+                let escalate_when_afk = presence.escalate_when_afk;
+                // 停止チャレンジが有効な場合は発火ごとに新しい問題を出題（合図は自動で止まるため除く）
+                let challenge = (settings.challenge.enabled && !source.is_chime())
+                    .then(|| Challenge::generate(settings.challenge.difficulty, random_u64()));
+                // 動きによる停止が有効な場合は鳴動中の条件として保持
+                let movement_dismiss = settings.movement_dismiss.clamped();
                 let ringing_duration = {
                     let mut app_state = state.lock().unwrap();
+                    app_state.challenge = challenge.clone();
+                    app_state.movement_dismiss = (movement_dismiss.mode
+                        != MovementDismissMode::Off
+                        && !source.is_chime())
+                    .then_some(movement_dismiss);
+                    app_state.movement_active_since = None;
+                    app_state.movement_satisfied = false;
                     app_state.is_ringing = true;
-                    app_state.ringing_duration_minutes
+                    app_state.snooze_until = None;
+                    match source {
+                        AlarmSource::Wake => app_state.next_alarm_at = None,
+                        AlarmSource::Countdown | AlarmSource::Nap => {
+                            app_state.countdown_ends_at = None
+                        }
+                        AlarmSource::Calendar | AlarmSource::Pomodoro => {}
+                    }
+                    app_state.ringing_started_at = Some(Utc::now());
+                    // 合図はポモドーロの設定の秒数だけ鳴らす
+                    let ringing_seconds = match source {
+                        AlarmSource::Pomodoro => settings.pomodoro.clone().clamped().ring_seconds,
+                        _ => app_state.ringing_duration_minutes * 60,
+                    };
+                    Duration::from_secs(ringing_seconds as u64)
                 };
+                send_alarm_progress(&state).await;
+                if escalation.enabled {
+                    send_alarm_intensity(&state, &escalation, escalate_when_afk).await;
+                }
+                if let Some(challenge) = challenge {
+                    let state_clone = state.clone();
+                    tokio::spawn(async move {
+                        send_challenge(&state_clone, &challenge).await;
+                    });
+                }
+                if !source.is_chime() {
+                    tokio::spawn({
+                        let state_clone = state.clone();
+                        async move {
+                            let time = Local::now().format("%H:%M").to_string();
+                            notify_chatbox(
+                                &state_clone,
+                                ChatboxEvent::AlarmFire,
+                                &[("time", time)],
+                            )
+                            .await;
+                        }
+                    });
+                }
 
                 // アラーム終了用のタイマーを作成
                 let state_clone = state.clone();
                 let timer_manager_clone = timer_manager.clone();
                 let ringing_handle = tokio::spawn(async move {
-                    // 設定したアラーム時間だけ待機
-                    sleep(Duration::from_secs(ringing_duration as u64 * 60)).await;
+                    // 設定したアラーム時間だけ待機し、その間は進捗を定期的に送信
+                    let ringing_end = Instant::now() + ringing_duration;
+                    loop {
+                        let now = Instant::now();
+                        if now >= ringing_end {
+                            break;
+                        }
+                        sleep((ringing_end - now).min(RINGING_PROGRESS_INTERVAL)).await;
+                        send_alarm_progress(&state_clone).await;
+                        if escalation.enabled {
+                            send_alarm_intensity(&state_clone, &escalation, escalate_when_afk)
+                                .await;
+                        }
+                    }
                     println!(
-                        "{} minutes of ringing completed. Auto-triggering snooze.",
-                        ringing_duration
+                        "{} seconds of ringing completed. Auto-triggering snooze.",
+                        ringing_duration.as_secs()
                     );
                     // アラーム終了イベントを発生
                     handle_timer_event(state_clone, timer_manager_clone, TimerEvent::RingingEnd)
                         .await;
                 });
 
-                // アラーム終了タイマーをアクティブに設定
+                // アラーム終了タイマーをアラームの種類に対応する枠に設定
                 if let Ok(mut timer_mgr) = timer_manager.lock() {
-                    timer_mgr.set_active_timer(ringing_handle);
+                    timer_mgr.set_alarm_timer(source, ringing_handle);
                 }
             }
             // スヌーズ終了またはアラーム終了時の処理
             TimerEvent::SnoozeEnd | TimerEvent::RingingEnd => {
+                // 合図はスヌーズせずにそのまま終了
+                let source = state
+                    .lock()
+                    .map(|app_state| app_state.alarm_source)
+                    .unwrap_or_default();
+                if source.is_chime() {
+                    finish_chime(state, timer_manager, source);
+                    return;
+                }
                 // スヌーズ回数を管理し、停止判定を行う
-                let (should_stop, snooze_duration) = {
+                let settings = load_settings();
+                let snooze_strategy = settings.snooze_strategy;
+                let presence = settings.presence;
+                let (source, should_stop, snooze_duration, snooze_count, max_snoozes) = {
                     let mut app_state = state.lock().unwrap();
                     if matches!(event, TimerEvent::SnoozeEnd) {
                         app_state.snooze_count += 1;
@@ -164,26 +498,77 @@ pub fn handle_timer_event(
                             "Manual snooze triggered. Count: {}/{}",
                             app_state.snooze_count, app_state.max_snoozes
                         );
+                        let detail = format!(
+                            "manual {}/{}",
+                            app_state.snooze_count, app_state.max_snoozes
+                        );
+                        app_state.log_event(EventKind::Snoozed, detail);
                     } else {
                         app_state.snooze_count += 1;
                         println!(
                             "Auto snooze triggered. Count: {}/{}",
                             app_state.snooze_count, app_state.max_snoozes
                         );
+                        let detail = format!(
+                            "auto {}/{}",
+                            app_state.snooze_count, app_state.max_snoozes
+                        );
+                        app_state.log_event(EventKind::Snoozed, detail);
+                    }
+                    if let Some(session) = app_state.sleep_session.as_mut() {
+                        session.snooze_count += 1;
                     }
-                    let should_stop = app_state.snooze_count > app_state.max_snoozes;
+                    // AFK中は寝ているとみなし、最大回数に達しても停止しない（上限あり）
+                    let source = app_state.alarm_source;
+                    let still_asleep = presence.blocks_auto_stop(
+                        source,
+                        app_state.is_afk,
+                        app_state.snooze_count,
+                        app_state.max_snoozes,
+                    );
+                    if still_asleep && app_state.snooze_count > app_state.max_snoozes {
+                        println!("Max snoozes reached while AFK. Continuing to snooze.");
+                    }
+                    let should_stop =
+                        app_state.snooze_count > app_state.max_snoozes && !still_asleep;
                     app_state.is_ringing = false; // アラームを停止
+                    app_state.ringing_started_at = None;
+                    // スヌーズ間隔を設定された方式で決定
+                    let snooze_duration = snooze_strategy.snooze_minutes(
+                        app_state.snooze_count,
+                        app_state.snooze_duration_minutes,
+                        random_u64(),
+                    );
                     if should_stop {
                         app_state.snooze_count = 0; // カウンターをリセット
+                        app_state.snooze_until = None;
+                        app_state.current_snooze_minutes = 0;
+                        app_state.log_event(EventKind::Stopped, "max snoozes reached");
                         println!("Max snoozes reached. Stopping alarm completely.");
+                        // 起床アラームの場合のみ睡眠セッションを終了（保存はロックの解放後）
+                        if app_state.alarm_source == AlarmSource::Wake {
+                            spawn_save_finished_session(&state, finish_session(&mut app_state));
+                        }
+                    } else {
+                        // スヌーズ後の再発火時刻を記録
+                        app_state.current_snooze_minutes = snooze_duration;
+                        app_state.snooze_until = Some(
+                            Utc::now() + chrono::Duration::minutes(snooze_duration as i64),
+                        );
                     }
-                    (should_stop, app_state.snooze_duration_minutes)
+                    (
+                        source,
+                        should_stop,
+                        snooze_duration,
+                        app_state.snooze_count,
+                        app_state.max_snoozes,
+                    )
                 };
 
                 // 現在動作中のタイマーをキャンセル
                 {
                     let mut timer_mgr = timer_manager.lock().unwrap();
-                    timer_mgr.cancel_active_timer();
+                    timer_mgr.cancel_alarm_timer(source);
                 }
 
                 // アラーム停止シグナルを送信
@@ -204,6 +589,10 @@ pub fn handle_timer_event(
                     }
                 });
 
+                send_alarm_progress(&state).await;
+                reset_alarm_intensity(&state).await;
+                clear_dismissal(&state).await;
+
                 // 最大スヌーズ回数に達した場合の処理
                 if should_stop {
                     // 最終停止シグナルをVRChatに送信
@@ -216,11 +605,30 @@ pub fn handle_timer_event(
                     {
                         eprintln!("Failed to send final alarm stop signal: {}", e);
                     }
-                    // 次のアラームを設定
-                    calculate_and_set_next_alarm(state, timer_manager).await;
+                    // 次のアラームを設定（他の種類のアラームは起床アラームのタイマーを止めていない）
+                    if source == AlarmSource::Wake {
+                        calculate_and_set_next_alarm(state, timer_manager).await;
+                    }
                     return;
                 }
 
+                // スヌーズをチャットボックスに通知
+                tokio::spawn({
+                    let state_clone = state.clone();
+                    async move {
+                        notify_chatbox(
+                            &state_clone,
+                            ChatboxEvent::Snoozed,
+                            &[
+                                ("count", snooze_count.to_string()),
+                                ("max", max_snoozes.to_string()),
+                                ("minutes", snooze_duration.to_string()),
+                            ],
+                        )
+                        .await;
+                    }
+                });
+
                 // スヌーズ間隔終了後のアラーム再発火用タイマーを作成
                 let state_clone = state.clone();
                 let timer_manager_clone = timer_manager.clone();
@@ -236,21 +644,48 @@ pub fn handle_timer_event(
                         .await;
                 });
 
-                // スヌーズタイマーをアクティブに設定
+                // スヌーズタイマーをアラームの種類に対応する枠に設定
                 if let Ok(mut timer_mgr) = timer_manager.lock() {
-                    timer_mgr.set_active_timer(snooze_handle);
+                    timer_mgr.set_alarm_timer(source, snooze_handle);
                 }
             }
             // 手動停止時の処理
             TimerEvent::Stop => {
                 // タイマーとアラーム状態をリセット
-                {
+                let source = {
                     let mut timer_mgr = timer_manager.lock().unwrap();
-                    timer_mgr.cancel_active_timer(); // タイマーをキャンセル
                     let mut app_state = state.lock().unwrap();
+                    // 鳴動中・スヌーズ中でなければ起床アラームのタイマーを対象にする
+                    let busy = app_state.is_ringing || app_state.snooze_until.is_some();
+                    let source = if busy {
+                        app_state.alarm_source
+                    } else {
+                        AlarmSource::Wake
+                    };
+                    timer_mgr.cancel_alarm_timer(source); // タイマーをキャンセル
                     app_state.is_ringing = false; // アラームを停止
                     app_state.snooze_count = 0; // スヌーズ回数をリセット
+                    app_state.snooze_until = None;
+                    app_state.current_snooze_minutes = 0;
+                    app_state.ringing_started_at = None;
+                    app_state.log_event(EventKind::Stopped, "manual");
                     println!("Alarm stopped completely.");
+                    // 起床アラームの場合のみ睡眠セッションを終了（保存はロックの解放後）
+                    if app_state.alarm_source == AlarmSource::Wake {
+                        spawn_save_finished_session(&state, finish_session(&mut app_state));
+                    }
+                    source
+                };
+                send_alarm_progress(&state).await;
+                reset_alarm_intensity(&state).await;
+                clear_dismissal(&state).await;
+                if !source.is_chime() {
+                    tokio::spawn({
+                        let state_clone = state.clone();
+                        async move {
+                            notify_chatbox(&state_clone, ChatboxEvent::Stopped, &[]).await;
+                        }
+                    });
                 }
 
                 // VRChatに停止シグナルを送信
@@ -263,9 +698,317 @@ pub fn handle_timer_event(
                 {
                     eprintln!("Failed to send alarm stop signal: {}", e);
                 }
-                // 次のアラームを設定
-                calculate_and_set_next_alarm(state, timer_manager).await;
+                // 次のアラームを設定（他の種類のアラームは起床アラームのタイマーを止めていない）
+                if source == AlarmSource::Wake {
+                    calculate_and_set_next_alarm(state, timer_manager).await;
+                }
             }
         }
     })
 }
+
+// ポモドーロの切り替えなどの合図の鳴動を終了（スヌーズ・停止の記録や通知は行わない）
+fn finish_chime(state: AppStateMutex, timer_manager: TimerManagerMutex, source: AlarmSource) {
+    if let Ok(mut app_state) = state.lock() {
+        app_state.is_ringing = false;
+        app_state.ringing_started_at = None;
+        app_state.snooze_count = 0;
+    }
+    // 鳴動終了のタイマー自身から呼ばれるため、送信はタイマーを止める前に別タスクで行う
+    tokio::spawn(async move {
+        if let Err(e) = send_osc_to_vrchat(
+            "/avatar/parameters/AlarmShouldFire",
+            vec![OscType::Bool(false)],
+            &state,
+        )
+        .await
+        {
+            eprintln!("Failed to send alarm stop signal: {}", e);
+        }
+        send_alarm_progress(&state).await;
+        reset_alarm_intensity(&state).await;
+    });
+    if let Ok(mut timer_mgr) = timer_manager.lock() {
+        timer_mgr.cancel_alarm_timer(source);
+    }
+}
+
+// おやすみ時間の終了までアラームを遅らせる
+fn defer_alarm_until(
+    state: AppStateMutex,
+    timer_manager: TimerManagerMutex,
+    source: AlarmSource,
+    until: chrono::DateTime<Local>,
+) {
+    let wait = until
+        .signed_duration_since(Local::now())
+        .to_std()
+        .unwrap_or_default();
+    if let Ok(mut app_state) = state.lock() {
+        match source {
+            AlarmSource::Wake => app_state.next_alarm_at = Some(until.with_timezone(&Utc)),
+            AlarmSource::Countdown | AlarmSource::Nap => {
+                app_state.countdown_ends_at = Some(until.with_timezone(&Utc))
+            }
+            AlarmSource::Calendar => {
+                app_state.next_calendar_alarm_at = Some(until.with_timezone(&Utc))
+            }
+            AlarmSource::Pomodoro => {}
+        }
+        app_state.log_event(
+            EventKind::AlarmSuppressed,
+            format!("deferred until {}", until.format("%H:%M")),
+        );
+    }
+
+    let state_clone = state.clone();
+    let timer_manager_clone = timer_manager.clone();
+    let deferred_handle = tokio::spawn(async move {
+        sleep(wait).await;
+        handle_timer_event(state_clone, timer_manager_clone, TimerEvent::AlarmFire).await;
+    });
+    // カウントダウン・仮眠・予定のアラームは起床アラームのタイマーを上書きしないよう別枠で待機
+    if let Ok(mut timer_mgr) = timer_manager.lock() {
+        timer_mgr.set_alarm_timer(source, deferred_handle);
+    }
+}
+
+// スマート起床の検知時間帯に入ったら動きを監視し、目覚めの兆候を検知したきっかけを返す
+async fn watch_for_stirring(
+    state: &AppStateMutex,
+    fire_at: Instant,
+    smart_wake: SmartWakeSettings,
+) -> WakeTrigger {
+    sleep_until(smart_wake.window_start(fire_at)).await;
+
+    // 検知開始時の姿勢を基準にし、開始後に受信した動きだけを判定に使う
+    let watch_started_at = Utc::now();
+    let baseline_upright = state
+        .lock()
+        .map(|app_state| app_state.motion.upright)
+        .unwrap_or_default();
+    println!("Smart wake window started ({} minutes).", smart_wake.window_minutes);
+
+    loop {
+        sleep(SMART_WAKE_POLL_INTERVAL).await;
+        let motion = match state.lock().map(|app_state| app_state.motion.clone()) {
+            Ok(motion) => motion,
+            Err(e) => {
+                eprintln!("Failed to lock state: {}", e);
+                continue;
+            }
+        };
+        if let Some(trigger) =
+            smart_wake.detect_stirring(&motion, baseline_upright, watch_started_at)
+        {
+            return trigger;
+        }
+    }
+}
+
+// アラーム発火前のサンライズを実行し、発火時刻まで明るさを送信する
+async fn run_sunrise_ramp(
+    state: &AppStateMutex,
+    fire_at: Instant,
+    window: Duration,
+    curve: RampCurve,
+) {
+    let ramp_start = fire_at.checked_sub(window).unwrap_or(fire_at);
+    sleep_until(ramp_start).await;
+    println!("Sunrise started ({} minutes before alarm)", window.as_secs() / 60);
+
+    loop {
+        let now = Instant::now();
+        if now >= fire_at {
+            break;
+        }
+        // 設定途中から開始した場合も経過割合に応じた明るさから始める
+        let progress =
+            now.saturating_duration_since(ramp_start).as_secs_f32() / window.as_secs_f32();
+        send_sunrise_level(state, curve.apply(progress)).await;
+        sleep_until((now + SUNRISE_UPDATE_INTERVAL).min(fire_at)).await;
+    }
+    send_sunrise_level(state, 1.0).await;
+}
+
+// サンライズの明るさをVRChatに送信
+async fn send_sunrise_level(state: &AppStateMutex, level: f32) {
+    if let Ok(mut app_state) = state.lock() {
+        app_state.sunrise_level = level;
+    }
+    if let Err(e) = send_osc_to_vrchat(
+        "/avatar/parameters/AlarmSunrise",
+        vec![OscType::Float(level)],
+        state,
+    )
+    .await
+    {
+        eprintln!("Failed to send sunrise level: {}", e);
+    }
+}
+
+// スヌーズ回数と鳴動経過時間に応じたアラームの強さをVRChatに送信
+async fn send_alarm_intensity(
+    state: &AppStateMutex,
+    escalation: &EscalationSettings,
+    escalate_when_afk: bool,
+) {
+    let intensity = {
+        let mut app_state = match state.lock() {
+            Ok(state) => state,
+            Err(e) => {
+                eprintln!("Failed to lock state: {}", e);
+                return;
+            }
+        };
+        let ringing_minutes = app_state
+            .ringing_started_at
+            .map(|started_at| {
+                Utc::now().signed_duration_since(started_at).num_seconds() as f32 / 60.0
+            })
+            .unwrap_or(0.0);
+        // AFK中は寝ているとみなし、設定に応じて上限まで強める
+        let intensity = if escalate_when_afk && app_state.is_afk {
+            escalation.max_intensity
+        } else {
+            escalation.intensity(app_state.snooze_count, ringing_minutes)
+        };
+        app_state.alarm_intensity = intensity;
+        intensity
+    };
+
+    if let Err(e) = send_osc_to_vrchat(
+        "/avatar/parameters/AlarmIntensity",
+        vec![OscType::Float(intensity)],
+        state,
+    )
+    .await
+    {
+        eprintln!("Failed to send alarm intensity: {}", e);
+    }
+}
+
+// 鳴動終了時にアラームの強さをリセット
+async fn reset_alarm_intensity(state: &AppStateMutex) {
+    let was_escalated = match state.lock() {
+        Ok(mut app_state) => std::mem::replace(&mut app_state.alarm_intensity, 0.0) > 0.0,
+        Err(e) => {
+            eprintln!("Failed to lock state: {}", e);
+            return;
+        }
+    };
+    if was_escalated {
+        if let Err(e) = send_osc_to_vrchat(
+            "/avatar/parameters/AlarmIntensity",
+            vec![OscType::Float(0.0)],
+            state,
+        )
+        .await
+        {
+            eprintln!("Failed to reset alarm intensity: {}", e);
+        }
+    }
+}
+
+// スヌーズ回数と鳴動の進捗をVRChatに送信
+async fn send_alarm_progress(state: &AppStateMutex) {
+    let (snooze_count, snoozes_remaining, ringing_progress) = {
+        let app_state = match state.lock() {
+            Ok(state) => state,
+            Err(e) => {
+                eprintln!("Failed to lock state: {}", e);
+                return;
+            }
+        };
+        // 鳴動開始からの経過時間を鳴動時間に対する割合に変換
+        let ringing_progress = match app_state.ringing_started_at {
+            Some(started_at) if app_state.is_ringing => {
+                let total_ms = app_state.ringing_duration_minutes as i64 * 60_000;
+                let elapsed_ms = Utc::now()
+                    .signed_duration_since(started_at)
+                    .num_milliseconds();
+                if total_ms > 0 {
+                    (elapsed_ms.clamp(0, total_ms) as f32) / (total_ms as f32)
+                } else {
+                    1.0
+                }
+            }
+            _ => 0.0,
+        };
+        (
+            app_state.snooze_count,
+            app_state.max_snoozes.saturating_sub(app_state.snooze_count),
+            ringing_progress,
+        )
+    };
+
+    let messages = vec![
+        OscMessage {
+            addr: "/avatar/parameters/SnoozeCount".to_string(),
+            args: vec![OscType::Int(snooze_count as i32)],
+        },
+        OscMessage {
+            addr: "/avatar/parameters/SnoozesRemaining".to_string(),
+            args: vec![OscType::Int(snoozes_remaining as i32)],
+        },
+        OscMessage {
+            addr: "/avatar/parameters/RingingProgress".to_string(),
+            args: vec![OscType::Float(ringing_progress)],
+        },
+    ];
+
+    if let Err(e) = send_osc_bundle_to_vrchat(messages, state).await {
+        eprintln!("Failed to send alarm progress: {}", e);
+    }
+}
+
+// 過ぎたスキップと休暇を設定から削除し、有効なスキップの設定を返す
+async fn expire_skips(
+    state: &AppStateMutex,
+    settings: &AlarmSettings,
+    now: chrono::DateTime<Local>,
+) -> SkipSettings {
+    let skip = settings.skip.clone().without_expired(now);
+    if skip == settings.skip {
+        return skip;
+    }
+    println!("Removing expired alarm skips");
+    let updated = AlarmSettings {
+        skip: skip.clone(),
+        ..settings.clone()
+    };
+    if let Err(e) = save_settings(&updated) {
+        eprintln!("Failed to save settings: {}", e);
+    }
+
+    // スキップが終わったことをアバターに反映
+    if skip.skip_date.is_none() && settings.skip.skip_date.is_some() {
+        send_skip_state(state, false).await;
+    }
+    skip
+}
+
+// 次回アラームとスヌーズ再発火の時刻情報を取得
+pub fn next_fire_info(state: &AppStateMutex) -> Result<NextFireInfo, String> {
+    let app_state = state
+        .lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    let now = Utc::now();
+
+    Ok(NextFireInfo {
+        next_alarm_at: app_state.next_alarm_at,
+        snooze_until: app_state.snooze_until,
+        minutes_until_alarm: app_state
+            .next_alarm_at
+            .map(|at| at.signed_duration_since(now).num_minutes().max(0)),
+        snooze_remaining_seconds: app_state
+            .snooze_until
+            .map(|at| at.signed_duration_since(now).num_seconds().max(0)),
+        countdown_ends_at: app_state.countdown_ends_at,
+        bedtime_at: app_state.bedtime_at,
+        next_skipped_date: app_state.next_skipped_date,
+        countdown_remaining_seconds: app_state
+            .countdown_ends_at
+            .map(|at| at.signed_duration_since(now).num_seconds().max(0)),
+    })
+}
@@ -1,5 +1,24 @@
-use chrono::{DateTime, Utc};
+use crate::bedtime::BedtimeSettings;
+use crate::calendar::{CalendarAlarm, CalendarImportSettings};
+use crate::chatbox::ChatboxSettings;
+use crate::countdown::CountdownSettings;
+use crate::cron::CronAlarmSettings;
+use crate::dismiss::{Challenge, ChallengeSettings, MovementDismissSettings};
+use crate::escalation::EscalationSettings;
+use crate::holiday::HolidaySettings;
+use crate::quiet::QuietHoursSettings;
+use crate::skip::SkipSettings;
+use crate::sleep::SleepSession;
+use crate::smart_wake::SmartWakeSettings;
+use crate::nap::NapSettings;
+use crate::pomodoro::{PomodoroSettings, PomodoroState};
+use crate::presence::PresenceSettings;
+use crate::snooze::SnoozeStrategy;
+use crate::solar::SolarAlarmSettings;
+use crate::stopwatch::Stopwatch;
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
 use std::sync::{Arc, Mutex};
 use tokio::task::JoinHandle;
 
@@ -18,6 +37,39 @@ pub struct AppState {
     pub max_snoozes: u32, // 最大スヌーズ回数
     pub ringing_duration_minutes: u32, // アラーム時間
     pub snooze_duration_minutes: u32, // スヌーズ間隔
+    pub next_alarm_at: Option<DateTime<Utc>>, // 次回アラーム発火時刻
+    pub snooze_until: Option<DateTime<Utc>>, // スヌーズ後の再発火時刻
+    pub current_snooze_minutes: u32, // 現在のスヌーズの待機分数
+    pub ringing_started_at: Option<DateTime<Utc>>, // 鳴動開始時刻
+    pub sunrise_level: f32, // 現在のサンライズの明るさ（0.0 - 1.0）
+    pub alarm_intensity: f32, // 現在のアラームの強さ（0.0 - 1.0）
+    pub last_chatbox_sent: Option<DateTime<Utc>>, // チャットボックスの最終送信時刻
+    pub challenge: Option<Challenge>, // 出題中の停止チャレンジ
+    pub motion: MotionState, // VRChatから受信した動きの情報
+    pub is_afk: bool, // VRChatでAFK状態かどうか
+    pub vr_mode: Option<bool>, // VRモードかどうか（未受信の場合はNone）
+    pub alarm_source: AlarmSource, // 鳴動中（または直近）のアラームの種類
+    pub countdown_ends_at: Option<DateTime<Utc>>, // カウントダウンの終了時刻
+    pub bedtime_at: Option<DateTime<Utc>>, // 次のアラームに対する就寝時刻
+    pub countdown_total_minutes: u32, // 開始したカウントダウンの分数
+    pub countdown_source: AlarmSource, // カウントダウンの種類（カウントダウンまたは仮眠）
+    pub countdown_selected_minutes: u32, // アバターで選択中のカウントダウンの分数
+    pub stopwatch: Stopwatch, // ストップウォッチの状態とラップの履歴
+    pub pomodoro: PomodoroState, // ポモドーロの現在のフェーズ
+    pub next_skipped_date: Option<NaiveDate>, // 祝日・スキップ・休暇のためにスキップされる次の日
+    pub skip_next_param: bool, // SkipNextAlarmパラメータの最後に送受信した値
+    #[serde(skip)]
+    pub calendar_alarms: Vec<CalendarAlarm>, // .icsファイルから読み込んだアラーム
+    pub next_calendar_alarm_at: Option<DateTime<Utc>>, // 次に予定のアラームまたは通知を鳴らす時刻
+    pub movement_dismiss: Option<MovementDismissSettings>, // 鳴動中の動きによる停止条件
+    pub movement_active_since: Option<DateTime<Utc>>, // 条件を満たす動きの開始時刻
+    pub movement_satisfied: bool, // 動きによる停止条件を満たしたかどうか
+    pub event_log: VecDeque<EventLogEntry>, // アラーム関連のイベント履歴
+    #[serde(skip)]
+    pub sleep_session: Option<SleepSession>, // 記録中の睡眠セッション
+    pub clock_enabled: bool, // 時計パラメータを送信するかどうか
+    pub clock_interval_seconds: u32, // 時計パラメータの送信間隔
+    pub timezone_offset_minutes: Option<i32>, // タイムゾーン（UTCからの分数、未設定はシステム設定）
 }
 
 // デフォルト値を設定
@@ -36,15 +88,125 @@ impl Default for AppState {
             max_snoozes: 5,
             ringing_duration_minutes: 15,
             snooze_duration_minutes: 9,
+            next_alarm_at: None,
+            snooze_until: None,
+            current_snooze_minutes: 0,
+            ringing_started_at: None,
+            sunrise_level: 0.0,
+            alarm_intensity: 0.0,
+            last_chatbox_sent: None,
+            challenge: None,
+            motion: MotionState::default(),
+            is_afk: false,
+            vr_mode: None,
+            alarm_source: AlarmSource::Wake,
+            countdown_ends_at: None,
+            bedtime_at: None,
+            countdown_total_minutes: 0,
+            countdown_source: AlarmSource::Countdown,
+            countdown_selected_minutes: 0,
+            stopwatch: Stopwatch::default(),
+            pomodoro: PomodoroState::default(),
+            next_skipped_date: None,
+            skip_next_param: false,
+            calendar_alarms: Vec::new(),
+            next_calendar_alarm_at: None,
+            movement_dismiss: None,
+            movement_active_since: None,
+            movement_satisfied: false,
+            event_log: VecDeque::new(),
+            sleep_session: None,
+            clock_enabled: false,
+            clock_interval_seconds: 60,
+            timezone_offset_minutes: None,
         }
     }
 }
 
+impl AppState {
+    // イベント履歴に記録（古いものから破棄）
+    pub fn log_event(&mut self, kind: EventKind, detail: impl Into<String>) {
+        if self.event_log.len() >= EVENT_LOG_CAPACITY {
+            self.event_log.pop_front();
+        }
+        self.event_log.push_back(EventLogEntry {
+            at: Utc::now(),
+            kind,
+            detail: detail.into(),
+        });
+    }
+}
+
 pub type AppStateMutex = Arc<Mutex<AppState>>;
 
+// イベント履歴の最大保持件数
+const EVENT_LOG_CAPACITY: usize = 200;
+
+// イベントの種類
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    AlarmFired,
+    Snoozed,
+    Stopped,
+    SleepStarted,
+    SleepEnded,
+    AlarmSuppressed,
+    CountdownStarted,
+    CountdownCancelled,
+}
+
+/// 鳴動しているアラームの種類
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlarmSource {
+    #[default]
+    Wake, // 時刻指定の起床アラーム
+    Countdown, // カウントダウンタイマー
+    Nap,       // 仮眠（カウントダウンの枠で動作）
+    Calendar,  // .icsファイルから読み込んだ予定
+    Pomodoro,  // ポモドーロのフェーズ切り替えの合図
+}
+
+impl AlarmSource {
+    // 起床のためのアラームかどうか（おやすみ時間の重要なアラームとして扱う）
+    pub fn is_wake(self) -> bool {
+        matches!(self, AlarmSource::Wake | AlarmSource::Nap)
+    }
+
+    // 短く鳴らすだけの合図かどうか（スヌーズ・停止チャレンジ・チャットボックス通知を行わない）
+    pub fn is_chime(self) -> bool {
+        self == AlarmSource::Pomodoro
+    }
+}
+
+// イベント履歴の項目
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventLogEntry {
+    pub at: DateTime<Utc>, // 発生時刻
+    pub kind: EventKind, // イベントの種類
+    pub detail: String, // 詳細
+}
+
+// VRChatの組み込みパラメータから受信した動きの情報
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MotionState {
+    pub velocity_magnitude: f32, // 移動速度
+    pub upright: f32, // 直立度（0.0 - 1.0）
+    pub grounded: bool, // 接地しているかどうか
+    pub angular_y: f32, // Y軸の回転速度
+    pub last_update: Option<DateTime<Utc>>, // 最終受信時刻
+}
+
 // タイマー管理
 pub struct TimerManager {
     pub active_timer_handle: Option<JoinHandle<()>>,
+    pub countdown_timer_handle: Option<JoinHandle<()>>,
+    pub stopwatch_handle: Option<JoinHandle<()>>,
+    pub pomodoro_handle: Option<JoinHandle<()>>,
+    pub pomodoro_alarm_handle: Option<JoinHandle<()>>,
+    pub calendar_timer_handle: Option<JoinHandle<()>>,
+    pub calendar_alarm_handle: Option<JoinHandle<()>>,
 }
 
 // タイマー管理の実装
@@ -52,6 +214,12 @@ impl TimerManager {
     pub fn new() -> Self {
         Self {
             active_timer_handle: None,
+            countdown_timer_handle: None,
+            stopwatch_handle: None,
+            pomodoro_handle: None,
+            pomodoro_alarm_handle: None,
+            calendar_timer_handle: None,
+            calendar_alarm_handle: None,
         }
     }
 
@@ -68,6 +236,107 @@ impl TimerManager {
         self.cancel_active_timer();
         self.active_timer_handle = Some(handle);
     }
+
+    // カウントダウンタイマーをキャンセル
+    pub fn cancel_countdown_timer(&mut self) {
+        if let Some(handle) = self.countdown_timer_handle.take() {
+            handle.abort();
+            println!("Countdown cancelled");
+        }
+    }
+
+    // カウントダウンタイマーを設定（起床アラームのタイマーとは独立して動作）
+    pub fn set_countdown_timer(&mut self, handle: JoinHandle<()>) {
+        self.cancel_countdown_timer();
+        self.countdown_timer_handle = Some(handle);
+    }
+
+    // ストップウォッチの送信タスクを停止
+    pub fn cancel_stopwatch_timer(&mut self) {
+        if let Some(handle) = self.stopwatch_handle.take() {
+            handle.abort();
+        }
+    }
+
+    // ストップウォッチの送信タスクを設定（アラームのタイマーとは独立して動作）
+    pub fn set_stopwatch_timer(&mut self, handle: JoinHandle<()>) {
+        self.cancel_stopwatch_timer();
+        self.stopwatch_handle = Some(handle);
+    }
+
+    // ポモドーロのタイマーを停止
+    pub fn cancel_pomodoro_timer(&mut self) {
+        if let Some(handle) = self.pomodoro_handle.take() {
+            handle.abort();
+            println!("Pomodoro cancelled");
+        }
+    }
+
+    // ポモドーロのタイマーを設定（アラームのタイマーとは独立して動作）
+    pub fn set_pomodoro_timer(&mut self, handle: JoinHandle<()>) {
+        self.cancel_pomodoro_timer();
+        self.pomodoro_handle = Some(handle);
+    }
+
+    // ポモドーロの切り替えの合図の鳴動用のタイマーを停止
+    pub fn cancel_pomodoro_alarm_timer(&mut self) {
+        if let Some(handle) = self.pomodoro_alarm_handle.take() {
+            handle.abort();
+        }
+    }
+
+    // ポモドーロの切り替えの合図の鳴動用のタイマーを設定（サイクルのタイマーは止めない）
+    pub fn set_pomodoro_alarm_timer(&mut self, handle: JoinHandle<()>) {
+        self.cancel_pomodoro_alarm_timer();
+        self.pomodoro_alarm_handle = Some(handle);
+    }
+
+    // 予定のアラームのタイマーを停止
+    pub fn cancel_calendar_timer(&mut self) {
+        if let Some(handle) = self.calendar_timer_handle.take() {
+            handle.abort();
+        }
+    }
+
+    // 予定のアラームのタイマーを設定（起床アラームのタイマーとは独立して動作）
+    pub fn set_calendar_timer(&mut self, handle: JoinHandle<()>) {
+        self.cancel_calendar_timer();
+        self.calendar_timer_handle = Some(handle);
+    }
+
+    // 予定のアラームの鳴動・スヌーズ用のタイマーを停止
+    pub fn cancel_calendar_alarm_timer(&mut self) {
+        if let Some(handle) = self.calendar_alarm_handle.take() {
+            handle.abort();
+        }
+    }
+
+    // 予定のアラームの鳴動・スヌーズ用のタイマーを設定（カレンダーの読み込み直しでは取り消さない）
+    pub fn set_calendar_alarm_timer(&mut self, handle: JoinHandle<()>) {
+        self.cancel_calendar_alarm_timer();
+        self.calendar_alarm_handle = Some(handle);
+    }
+
+    // アラームの種類に対応する鳴動・スヌーズ用のタイマーをキャンセル
+    pub fn cancel_alarm_timer(&mut self, source: AlarmSource) {
+        match source {
+            AlarmSource::Wake => self.cancel_active_timer(),
+            AlarmSource::Countdown | AlarmSource::Nap => self.cancel_countdown_timer(),
+            AlarmSource::Calendar => self.cancel_calendar_alarm_timer(),
+            AlarmSource::Pomodoro => self.cancel_pomodoro_alarm_timer(),
+        }
+    }
+
+    // アラームの種類に対応する枠に鳴動・スヌーズ用のタイマーを設定
+    // （カウントダウン・仮眠・予定のアラームが起床アラームのタイマーを上書きしないようにする）
+    pub fn set_alarm_timer(&mut self, source: AlarmSource, handle: JoinHandle<()>) {
+        match source {
+            AlarmSource::Wake => self.set_active_timer(handle),
+            AlarmSource::Countdown | AlarmSource::Nap => self.set_countdown_timer(handle),
+            AlarmSource::Calendar => self.set_calendar_alarm_timer(handle),
+            AlarmSource::Pomodoro => self.set_pomodoro_alarm_timer(handle),
+        }
+    }
 }
 
 pub type TimerManagerMutex = Arc<Mutex<TimerManager>>;
@@ -78,9 +347,69 @@ pub struct AlarmSettings {
     pub alarm_hour: i32,
     pub alarm_minute: i32,
     pub alarm_is_on: bool,
+    #[serde(default)]
+    pub alarm_weekdays: Vec<u32>, // アラームを鳴らす曜日（0=日曜日、空の場合は毎日）
+    #[serde(default)]
+    pub alarm_type: AlarmType, // アラーム時刻の決め方
+    #[serde(default)]
+    pub solar_alarm: SolarAlarmSettings, // 日の出を基準にしたアラームの設定
     pub max_snoozes: u32,
     pub ringing_duration_minutes: u32,
     pub snooze_duration_minutes: u32,
+    #[serde(default)]
+    pub send_countdown_parameters: bool, // 残り時間パラメータを送信するかどうか
+    #[serde(default)]
+    pub clock_enabled: bool, // 時計パラメータを送信するかどうか
+    #[serde(default = "default_clock_interval_seconds")]
+    pub clock_interval_seconds: u32, // 時計パラメータの送信間隔
+    #[serde(default)]
+    pub timezone_offset_minutes: Option<i32>, // タイムゾーン（UTCからの分数、未設定はシステム設定）
+    #[serde(default)]
+    pub sunrise_enabled: bool, // アラーム前のサンライズを有効にするかどうか
+    #[serde(default = "default_sunrise_minutes")]
+    pub sunrise_minutes: u32, // サンライズを開始するアラーム前の分数
+    #[serde(default)]
+    pub sunrise_curve: RampCurve, // サンライズの明るさの変化曲線
+    #[serde(default)]
+    pub escalation: EscalationSettings, // アラームの強さの段階的な上昇
+    #[serde(default)]
+    pub snooze_strategy: SnoozeStrategy, // スヌーズ間隔の決め方
+    #[serde(default)]
+    pub chatbox: ChatboxSettings, // チャットボックス通知の設定
+    #[serde(default)]
+    pub challenge: ChallengeSettings, // 停止チャレンジの設定
+    #[serde(default)]
+    pub movement_dismiss: MovementDismissSettings, // 動きによる停止の設定
+    #[serde(default)]
+    pub smart_wake: SmartWakeSettings, // 寝返りなどを検知して早めに起こす設定
+    #[serde(default)]
+    pub presence: PresenceSettings, // アラームの種類ごとのAFK・VRモードに応じた動作
+    #[serde(default)]
+    pub quiet_hours: QuietHoursSettings, // 通知を抑制するおやすみ時間
+    #[serde(default)]
+    pub countdown_timer: CountdownSettings, // カウントダウンタイマーの設定
+    #[serde(default)]
+    pub pomodoro: PomodoroSettings, // ポモドーロの設定
+    #[serde(default)]
+    pub nap: NapSettings, // 仮眠の設定
+    #[serde(default)]
+    pub bedtime: BedtimeSettings, // 就寝リマインダーの設定
+    #[serde(default)]
+    pub holiday: HolidaySettings, // 祝日の設定
+    #[serde(default)]
+    pub skip: SkipSettings, // 次回のスキップと休暇の設定
+    #[serde(default)]
+    pub calendar_import: CalendarImportSettings, // .icsファイルの読み込みの設定
+    #[serde(default)]
+    pub cron_alarm: CronAlarmSettings, // cron式によるアラームの設定
+}
+
+fn default_clock_interval_seconds() -> u32 {
+    60
+}
+
+fn default_sunrise_minutes() -> u32 {
+    15
 }
 
 // アラーム設定のデフォルト値を設定
@@ -90,13 +419,85 @@ impl Default for AlarmSettings {
             alarm_hour: 7,
             alarm_minute: 0,
             alarm_is_on: false,
+            alarm_weekdays: Vec::new(),
+            alarm_type: AlarmType::default(),
+            solar_alarm: SolarAlarmSettings::default(),
             max_snoozes: 5,
             ringing_duration_minutes: 15,
             snooze_duration_minutes: 9,
+            send_countdown_parameters: false,
+            clock_enabled: false,
+            clock_interval_seconds: default_clock_interval_seconds(),
+            timezone_offset_minutes: None,
+            sunrise_enabled: false,
+            sunrise_minutes: default_sunrise_minutes(),
+            sunrise_curve: RampCurve::default(),
+            escalation: EscalationSettings::default(),
+            snooze_strategy: SnoozeStrategy::default(),
+            chatbox: ChatboxSettings::default(),
+            challenge: ChallengeSettings::default(),
+            movement_dismiss: MovementDismissSettings::default(),
+            smart_wake: SmartWakeSettings::default(),
+            presence: PresenceSettings::default(),
+            quiet_hours: QuietHoursSettings::default(),
+            countdown_timer: CountdownSettings::default(),
+            pomodoro: PomodoroSettings::default(),
+            nap: NapSettings::default(),
+            bedtime: BedtimeSettings::default(),
+            holiday: HolidaySettings::default(),
+            skip: SkipSettings::default(),
+            calendar_import: CalendarImportSettings::default(),
+            cron_alarm: CronAlarmSettings::default(),
         }
     }
 }
 
+/// アラーム時刻の決め方
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlarmType {
+    #[default]
+    Fixed, // 設定した時刻
+    Sunrise, // 日の出を基準にした時刻（緯度・経度から毎日計算）
+    Cron, // cron式に一致する時刻
+}
+
+// サンライズの明るさの変化曲線
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RampCurve {
+    #[default]
+    Linear,
+    EaseIn,
+    Exponential,
+}
+
+impl RampCurve {
+    // 進捗（0.0 - 1.0）を曲線に沿った明るさに変換
+    pub fn apply(self, progress: f32) -> f32 {
+        let progress = progress.clamp(0.0, 1.0);
+        match self {
+            RampCurve::Linear => progress,
+            RampCurve::EaseIn => progress * progress,
+            // 2^(10x) を 0.0 - 1.0 に正規化
+            RampCurve::Exponential => (2f32.powf(10.0 * progress) - 1.0) / 1023.0,
+        }
+    }
+}
+
+// 次回発火時刻の情報
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NextFireInfo {
+    pub next_alarm_at: Option<DateTime<Utc>>, // 次回アラーム発火時刻
+    pub snooze_until: Option<DateTime<Utc>>, // スヌーズ後の再発火時刻
+    pub minutes_until_alarm: Option<i64>, // 次回アラームまでの分数
+    pub snooze_remaining_seconds: Option<i64>, // スヌーズ残り秒数
+    pub countdown_ends_at: Option<DateTime<Utc>>, // カウントダウンの終了時刻
+    pub countdown_remaining_seconds: Option<i64>, // カウントダウンの残り秒数
+    pub bedtime_at: Option<DateTime<Utc>>, // 次のアラームに対する就寝時刻
+    pub next_skipped_date: Option<NaiveDate>, // 祝日・スキップ・休暇のためにスキップされる次の日
+}
+
 // タイマーイベント
 #[derive(Debug, Clone)]
 pub enum TimerEvent {
@@ -1,4 +1,7 @@
 // ユーティリティ関数
+use chrono::{DateTime, FixedOffset, Local, Utc};
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
 
 // 時間をVRChatの形式に変換
 pub fn hour_to_vrc_float(hour: i32) -> f32 {
@@ -23,3 +26,70 @@ pub fn vrc_float_to_minute(value: f32) -> i32 {
     let minute = (value * 100.0).round() as i32;
     minute.clamp(0, 59)
 }
+
+// 最大スヌーズ回数を有効範囲に丸め込み
+pub fn clamp_max_snoozes(value: u32) -> u32 {
+    value.clamp(1, 20)
+}
+
+// 鳴動時間を有効範囲に丸め込み
+pub fn clamp_ringing_duration_minutes(value: u32) -> u32 {
+    value.clamp(1, 60)
+}
+
+// スヌーズ間隔を有効範囲に丸め込み
+pub fn clamp_snooze_duration_minutes(value: u32) -> u32 {
+    value.clamp(1, 30)
+}
+
+// 残り分数をVRChatの形式に変換（1日 = 1.0）
+pub fn minutes_until_to_vrc_float(minutes: i64) -> f32 {
+    (minutes.clamp(0, 1440) as f32) / 1440.0
+}
+
+// 残り時間の割合をVRChatの形式に変換（0.0 - 1.0）
+pub fn remaining_ratio_to_vrc_float(remaining_seconds: i64, total_seconds: i64) -> f32 {
+    if total_seconds <= 0 {
+        return 0.0;
+    }
+    (remaining_seconds.clamp(0, total_seconds) as f32) / (total_seconds as f32)
+}
+
+// 秒をVRChatの形式に変換
+pub fn second_to_vrc_float(second: i32) -> f32 {
+    let clamped_second = second.clamp(0, 59);
+    (clamped_second as f32) / 100.0
+}
+
+// 曜日をVRChatの形式に変換（0=日曜日、6=土曜日）
+pub fn weekday_to_vrc_float(weekday: i32) -> f32 {
+    let clamped_weekday = weekday.clamp(0, 6);
+    (clamped_weekday as f32) / 100.0
+}
+
+// 日をVRChatの形式に変換
+pub fn day_to_vrc_float(day: i32) -> f32 {
+    let clamped_day = day.clamp(1, 31);
+    (clamped_day as f32) / 100.0
+}
+
+// 設定されたタイムゾーンの現在時刻を取得（未設定の場合はシステムのローカル時刻）
+pub fn now_in_timezone(offset_minutes: Option<i32>) -> DateTime<FixedOffset> {
+    let now = Utc::now();
+    match offset_minutes.and_then(|minutes| FixedOffset::east_opt(minutes * 60)) {
+        Some(offset) => now.with_timezone(&offset),
+        None => now.with_timezone(&Local).fixed_offset(),
+    }
+}
+
+// 乱数値を取得（暗号用途ではない）
+pub fn random_u64() -> u64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u128(Utc::now().timestamp_nanos_opt().unwrap_or_default() as u128);
+    hasher.finish()
+}
+
+// ラジアルメニューの値（0.0-1.0）をカウントダウンの分数（0-120分）に変換
+pub fn vrc_float_to_countdown_minutes(value: f32) -> u32 {
+    (value.clamp(0.0, 1.0) * 120.0).round() as u32
+}
@@ -18,6 +18,7 @@ interface AppState {
   max_snoozes: number; // 最大スヌーズ回数
   ringing_duration_minutes: number; // アラーム時間
   snooze_duration_minutes: number; // スヌーズ間隔
+  next_skipped_date: string | null; // 祝日・スキップ・休暇のためにスキップされる次の日（YYYY-MM-DD）
 }
 
 // アラーム設定の型
@@ -25,9 +26,38 @@ interface AlarmSettings {
   alarm_hour: number; // アラーム時間
   alarm_minute: number; // アラーム分
   alarm_is_on: boolean; // アラームがオンかどうか
+  max_snoozes: number; // 最大スヌーズ回数
+  ringing_duration_minutes: number; // アラーム時間
+  snooze_duration_minutes: number; // スヌーズ間隔
 }
 
 // アップデート情報の型
+interface SkippedEvent {
+  summary: string; // 予定の件名
+  start: string | null; // 開始日時（DTSTARTがない場合はnull）
+  reason: // 読み込めなかった理由
+    | { type: "missing_start" }
+    | { type: "foreign_time_zone"; tzid: string }
+    | { type: "unsupported_rule"; error: string };
+}
+
+interface ParsedCalendar {
+  alarms: unknown[]; // 読み込んだ予定
+  skipped: SkippedEvent[]; // 読み込めなかった予定
+}
+
+// 予定を読み込めなかった理由を表示用の文字列に変換
+function skippedReasonText(reason: SkippedEvent["reason"]): string {
+  switch (reason.type) {
+    case "missing_start":
+      return "開始日時がありません";
+    case "foreign_time_zone":
+      return `PCと異なるタイムゾーンです（${reason.tzid}）`;
+    case "unsupported_rule":
+      return `対応していない繰り返しです（${reason.error}）`;
+  }
+}
+
 interface UpdateInfo {
   current_version: string;
   latest_version: string;
@@ -91,6 +121,37 @@ function App() {
     });
   }
 
+  // .icsファイルを選択して予定のアラームを読み込み
+  async function importCalendar() {
+    try {
+      const { open, message } = await import("@tauri-apps/plugin-dialog");
+      const path = await open({ filters: [{ name: "iCalendar", extensions: ["ics"] }] });
+      if (!path) return;
+      const { alarms, skipped } = await invoke<ParsedCalendar>("import_calendar", { path });
+      const lines = [`${alarms.length}件の予定を読み込みました`];
+      if (skipped.length > 0) {
+        lines.push(`${skipped.length}件の予定は読み込めませんでした:`);
+        lines.push(...skipped.map((event) => `・${event.summary}: ${skippedReasonText(event.reason)}`));
+      }
+      await message(lines.join("\n"), { title: "カレンダー読込", kind: skipped.length > 0 ? "warning" : "info" });
+    } catch (error) {
+      console.error("カレンダーの読み込みに失敗しました:", error);
+    }
+  }
+
+  // アラームを.icsファイルに書き出し
+  async function exportCalendar() {
+    try {
+      const { save, message } = await import("@tauri-apps/plugin-dialog");
+      const path = await save({ defaultPath: "alarms.ics", filters: [{ name: "iCalendar", extensions: ["ics"] }] });
+      if (!path) return;
+      const count = await invoke<number>("export_alarm_calendar", { path });
+      await message(`${count}件の予定を書き出しました`, { title: "カレンダー書出", kind: "info" });
+    } catch (error) {
+      console.error("カレンダーの書き出しに失敗しました:", error);
+    }
+  }
+
   // アップデート確認
   async function checkForUpdates() {
     try {
@@ -182,11 +243,12 @@ VRChat Inc. とは関係ありません。`;
     if (appState?.is_ringing) height += 28; // アラーム中の場合は28px追加
     if (isExpanded) {
       height += 148; // 基本設定パネル + ライセンステキスト分（138 + 10）
+      if (appState?.next_skipped_date) height += 20; // 次回スキップ日を表示する場合は20px追加
       const advancedDetails = document.querySelector(".settings-details");
-      if (advancedDetails?.hasAttribute("open")) height += 120; // 詳細設定が開いている場合は120px追加
+      if (advancedDetails?.hasAttribute("open")) height += 172; // 詳細設定が開いている場合は172px追加
     }
     await appWindow.setSize(new LogicalSize(220, height)); // ウィンドウサイズを更新
-  }, [isExpanded, appState?.is_ringing]);
+  }, [isExpanded, appState?.is_ringing, appState?.next_skipped_date]);
 
   // 時間をフォーマット
   const formatTime = (hour: number, minute: number) => {
@@ -231,6 +293,9 @@ VRChat Inc. とは関係ありません。`;
       setTimerHour(settings.alarm_hour);
       setTimerMinute(settings.alarm_minute);
       setAlarmIsOn(settings.alarm_is_on);
+      setMaxSnoozes(settings.max_snoozes);
+      setRingingDuration(settings.ringing_duration_minutes);
+      setSnoozeDuration(settings.snooze_duration_minutes);
     });
 
     return () => {
@@ -320,6 +385,11 @@ VRChat Inc. とは関係ありません。`;
             </button>
           </div>
 
+          {/* 次回スキップ日 */}
+          {appState?.next_skipped_date && (
+            <div className="skip-notice">次回スキップ: {appState.next_skipped_date}</div>
+          )}
+
           {/* 詳細設定 */}
           <details className="settings-details" onToggle={updateWindowSize}>
             <summary>詳細設定</summary>
@@ -363,6 +433,12 @@ VRChat Inc. とは関係ありません。`;
               <button type="button" onClick={saveTimerSettings} className="save-btn-small">
                 タイマー設定保存
               </button>
+              <button type="button" onClick={importCalendar} className="save-btn-small">
+                カレンダー読込
+              </button>
+              <button type="button" onClick={exportCalendar} className="save-btn-small">
+                カレンダー書出
+              </button>
             </div>
           </details>
 